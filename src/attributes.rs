@@ -1,7 +1,7 @@
 use serde_json::Value as JsonValue;
 
 use crate::error::{ConversionError, Result};
-use crate::protos::onnx::{AttributeProto, attribute_proto};
+use crate::protos::onnx::{AttributeProto, TensorProto, attribute_proto};
 
 #[derive(Debug, Clone)]
 pub enum AttrValue {
@@ -21,6 +21,11 @@ impl<'a> AttrParser<'a> {
         Self { attrs }
     }
 
+    /// Whether an attribute named `name` is present, whatever its type.
+    pub fn has(&self, name: &str) -> bool {
+        self.attrs.iter().any(|a| a.name == name)
+    }
+
     pub fn get_int(&self, name: &str) -> Option<i64> {
         let a = self.attrs.iter().find(|a| a.name == name)?;
         if a.r#type == attribute_proto::AttributeType::Int as i32 {
@@ -65,6 +70,15 @@ impl<'a> AttrParser<'a> {
             None
         }
     }
+
+    pub fn get_tensor(&self, name: &str) -> Option<&'a TensorProto> {
+        let a = self.attrs.iter().find(|a| a.name == name)?;
+        if a.r#type == attribute_proto::AttributeType::Tensor as i32 {
+            a.t.as_ref()
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Default)]
@@ -132,6 +146,17 @@ impl AttrBuilder {
         self
     }
 
+    pub fn add_tensor(mut self, name: &str, value: TensorProto) -> Self {
+        let a = AttributeProto {
+            name: name.to_string(),
+            r#type: attribute_proto::AttributeType::Tensor as i32,
+            t: Some(value),
+            ..Default::default()
+        };
+        self.attrs.push(a);
+        self
+    }
+
     pub fn build(self) -> Vec<AttributeProto> {
         self.attrs
     }
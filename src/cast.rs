@@ -0,0 +1,331 @@
+//! Element-wise conversion of [`TensorData`] between data types.
+
+use crate::data_types::DataType;
+use crate::error::{ConversionError, Result};
+use crate::half_float::HalfFormat;
+use crate::minifloat::{self, MiniFloatFormat};
+use crate::tensor_data::{PackedBits, TensorData};
+
+/// What [`TensorData::cast`] does with values the target type cannot hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CastMode {
+    /// Clamp to the nearest representable value. NaN becomes zero for integer
+    /// targets; infinities clamp for float formats without them.
+    Saturate,
+    /// Integers wrap around in two's complement (floats are truncated first
+    /// and saturate at the `i128` range). Float targets do not wrap: values
+    /// overflow to infinity, or NaN for 8-bit formats without infinities, and
+    /// negative values become NaN for the unsigned `Float8E8M0`.
+    Wrap,
+    /// Fail with [`ConversionError::ValueOutOfRange`] if any value does not fit.
+    #[default]
+    Checked,
+}
+
+/// Result of [`TensorData::cast`].
+#[derive(Debug, Clone)]
+pub struct CastOutput {
+    pub data: TensorData,
+    /// Elements that did not fit the target type and were saturated or
+    /// wrapped; always zero in [`CastMode::Checked`].
+    pub out_of_range: usize,
+}
+
+/// Source elements widened to a type that holds every value exactly.
+enum Elements {
+    Float(Vec<f64>),
+    Int(Vec<i128>),
+}
+
+impl TensorData {
+    /// Converts every element to `to`.
+    ///
+    /// Float targets round to nearest-even (`Float16`/`BFloat16` via
+    /// [`HalfFormat`], 8-bit and 4-bit floats via [`minifloat::encode_f64`]),
+    /// once from the widened source value; integers above 2^53 are first
+    /// narrowed to `f64` with round-to-odd, which keeps that rounding exact.
+    /// Float to integer truncates toward zero. Any non-zero value, including
+    /// NaN, is `true`.
+    pub fn cast(&self, to: DataType, mode: CastMode) -> Result<CastOutput> {
+        let elements = Elements::from_data(self)?;
+        let mut out_of_range = OutOfRange::default();
+
+        let data = if to == DataType::Bool {
+            TensorData::Bool(match elements {
+                Elements::Float(v) => v.into_iter().map(|x| x != 0.0).collect(),
+                Elements::Int(v) => v.into_iter().map(|x| x != 0).collect(),
+            })
+        } else if to.is_floating() {
+            let values = match elements {
+                Elements::Float(v) => v,
+                Elements::Int(v) if to == DataType::Float64 => {
+                    v.into_iter().map(|x| x as f64).collect()
+                }
+                Elements::Int(v) => v.into_iter().map(round_to_odd).collect(),
+            };
+            let values = values
+                .into_iter()
+                .enumerate()
+                .map(|(i, x)| {
+                    let fits = fits_float(x, &to);
+                    if !fits {
+                        out_of_range.record(i, x);
+                    }
+                    if fits || x.is_nan() {
+                        x
+                    } else if mode == CastMode::Saturate {
+                        x.clamp(to.min_value(), to.max_value())
+                    } else if to == DataType::Float8E8M0 && x < 0.0 {
+                        // there is no sign bit to keep
+                        f64::NAN
+                    } else {
+                        x
+                    }
+                })
+                .collect();
+            floats_to_data(values, &to, mode == CastMode::Saturate)
+        } else {
+            let (min, max) = int_range(&to);
+            let values = match elements {
+                Elements::Int(v) => v,
+                Elements::Float(v) => v
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, x)| {
+                        if x.is_nan() {
+                            out_of_range.record(i, x);
+                            0
+                        } else {
+                            // `as` saturates at the i128 range
+                            x.trunc() as i128
+                        }
+                    })
+                    .collect(),
+            };
+            let values = values
+                .into_iter()
+                .enumerate()
+                .map(|(i, x)| {
+                    if (min..=max).contains(&x) {
+                        return x;
+                    }
+                    out_of_range.record(i, x);
+                    match mode {
+                        CastMode::Wrap => wrap(x, &to),
+                        _ => x.clamp(min, max),
+                    }
+                })
+                .collect();
+            ints_to_data(values, &to)
+        };
+
+        if let (CastMode::Checked, Some((index, value))) = (mode, &out_of_range.first) {
+            return Err(ConversionError::ValueOutOfRange(format!(
+                "{} of {} elements do not fit in {to}, first is element {index} ({value})",
+                out_of_range.count,
+                data.count()
+            )));
+        }
+        Ok(CastOutput {
+            data,
+            out_of_range: out_of_range.count,
+        })
+    }
+}
+
+#[derive(Default)]
+struct OutOfRange {
+    count: usize,
+    first: Option<(usize, String)>,
+}
+
+impl OutOfRange {
+    fn record(&mut self, index: usize, value: impl std::fmt::Display) {
+        self.count += 1;
+        if self.first.is_none() {
+            self.first = Some((index, value.to_string()));
+        }
+    }
+}
+
+impl Elements {
+    fn from_data(data: &TensorData) -> Result<Self> {
+        let floats = |v: Vec<f64>| Ok(Elements::Float(v));
+        let ints = |v: Vec<i128>| Ok(Elements::Int(v));
+        let mini = |format, codes: &[u8]| {
+            floats(
+                codes
+                    .iter()
+                    .map(|&c| minifloat::decode(format, c) as f64)
+                    .collect(),
+            )
+        };
+        match data {
+            TensorData::Raw(_) => Err(ConversionError::InvalidTensorData(
+                "cannot cast undecoded raw tensor data".to_string(),
+            )),
+            TensorData::Float32(v) => floats(v.iter().map(|&x| x as f64).collect()),
+            TensorData::Float64(v) => floats(v.clone()),
+            TensorData::Float16(v) => floats(
+                v.iter()
+                    .map(|&b| half::f16::from_bits(b).to_f64())
+                    .collect(),
+            ),
+            TensorData::BFloat16(v) => floats(
+                v.iter()
+                    .map(|&b| half::bf16::from_bits(b).to_f64())
+                    .collect(),
+            ),
+            TensorData::Float8E4M3FN(v) => mini(MiniFloatFormat::E4M3FN, v),
+            TensorData::Float8E4M3FNUZ(v) => mini(MiniFloatFormat::E4M3FNUZ, v),
+            TensorData::Float8E5M2(v) => mini(MiniFloatFormat::E5M2, v),
+            TensorData::Float8E5M2FNUZ(v) => mini(MiniFloatFormat::E5M2FNUZ, v),
+            TensorData::Float8E8M0(v) => mini(MiniFloatFormat::E8M0, v),
+            TensorData::Float4E2M1(p) => mini(MiniFloatFormat::E2M1, &p.unpack(4)),
+            TensorData::Int64(v) => ints(v.iter().map(|&x| x as i128).collect()),
+            TensorData::Int32(v) => ints(v.iter().map(|&x| x as i128).collect()),
+            TensorData::Int16(v) => ints(v.iter().map(|&x| x as i128).collect()),
+            TensorData::Int8(v) => ints(v.iter().map(|&x| x as i128).collect()),
+            TensorData::Uint64(v) => ints(v.iter().map(|&x| x as i128).collect()),
+            TensorData::Uint32(v) => ints(v.iter().map(|&x| x as i128).collect()),
+            TensorData::Uint16(v) => ints(v.iter().map(|&x| x as i128).collect()),
+            TensorData::Uint8(v) => ints(v.iter().map(|&x| x as i128).collect()),
+            TensorData::Bool(v) => ints(v.iter().map(|&x| x as i128).collect()),
+            TensorData::Int4(p) => ints(p.unpack_signed(4).into_iter().map(i128::from).collect()),
+            TensorData::Int2(p) => ints(p.unpack_signed(2).into_iter().map(i128::from).collect()),
+            TensorData::Uint4(p) => ints(p.unpack(4).into_iter().map(i128::from).collect()),
+            TensorData::Uint2(p) => ints(p.unpack(2).into_iter().map(i128::from).collect()),
+        }
+    }
+}
+
+/// Whether `to` can hold `x`: finite values must not round past its largest
+/// finite value, and infinities and NaN need a format that has them.
+fn fits_float(x: f64, to: &DataType) -> bool {
+    let format = MiniFloatFormat::from_data_type(to);
+    if x.is_nan() {
+        return format.is_none_or(|f| f.nan_code().is_some());
+    }
+    if x.is_infinite() {
+        return format.is_none_or(|f| f == MiniFloatFormat::E5M2);
+    }
+    match (to, format) {
+        (DataType::Float32, _) => (x as f32).is_finite(),
+        (DataType::Float16, _) => !HalfFormat::F16.is_infinite(round_half(HalfFormat::F16, x)),
+        (DataType::BFloat16, _) => !HalfFormat::BF16.is_infinite(round_half(HalfFormat::BF16, x)),
+        // E8M0 is unsigned
+        (_, Some(MiniFloatFormat::E8M0)) if x < 0.0 => false,
+        (_, Some(format)) => !minifloat::overflows(format, x),
+        _ => true,
+    }
+}
+
+/// `x` as an `f64` whose discarded low bits are folded into the last kept
+/// bit. Rounding this to a format with at most 51 significand bits gives the
+/// same result as rounding `x` itself.
+fn round_to_odd(x: i128) -> f64 {
+    let magnitude = x.unsigned_abs();
+    let bits = 128 - magnitude.leading_zeros();
+    let value = if bits <= f64::MANTISSA_DIGITS {
+        magnitude as f64
+    } else {
+        let shift = bits - f64::MANTISSA_DIGITS;
+        let sticky = magnitude & ((1 << shift) - 1) != 0;
+        ((magnitude >> shift) | sticky as u128) as f64 * 2f64.powi(shift as i32)
+    };
+    if x < 0 { -value } else { value }
+}
+
+/// Rounds `x` to a 16-bit float once. `half`'s own `f64` conversions drop
+/// the low mantissa bits before rounding, so narrow to `f32` with
+/// round-to-odd instead, which the second rounding cannot tell apart from `x`.
+fn round_half(format: HalfFormat, x: f64) -> u16 {
+    let nearest = x as f32;
+    if !nearest.is_finite() || nearest as f64 == x {
+        return format.from_f32(nearest);
+    }
+    let mut bits = nearest.to_bits();
+    if (nearest as f64).abs() > x.abs() {
+        // step back toward zero, then mark the value inexact
+        bits -= 1;
+    }
+    format.from_f32(f32::from_bits(bits | 1))
+}
+
+fn floats_to_data(values: Vec<f64>, to: &DataType, saturate: bool) -> TensorData {
+    let encode = |format| -> Vec<u8> {
+        values
+            .iter()
+            .map(|&x| minifloat::encode_f64(format, x, saturate))
+            .collect()
+    };
+    match to {
+        DataType::Float32 => TensorData::Float32(values.iter().map(|&x| x as f32).collect()),
+        DataType::Float64 => TensorData::Float64(values),
+        DataType::Float16 => TensorData::Float16(
+            values
+                .iter()
+                .map(|&x| round_half(HalfFormat::F16, x))
+                .collect(),
+        ),
+        DataType::BFloat16 => TensorData::BFloat16(
+            values
+                .iter()
+                .map(|&x| round_half(HalfFormat::BF16, x))
+                .collect(),
+        ),
+        DataType::Float8E4M3FN => TensorData::Float8E4M3FN(encode(MiniFloatFormat::E4M3FN)),
+        DataType::Float8E4M3FNUZ => TensorData::Float8E4M3FNUZ(encode(MiniFloatFormat::E4M3FNUZ)),
+        DataType::Float8E5M2 => TensorData::Float8E5M2(encode(MiniFloatFormat::E5M2)),
+        DataType::Float8E5M2FNUZ => TensorData::Float8E5M2FNUZ(encode(MiniFloatFormat::E5M2FNUZ)),
+        DataType::Float8E8M0 => TensorData::Float8E8M0(encode(MiniFloatFormat::E8M0)),
+        DataType::Float4E2M1 => {
+            TensorData::Float4E2M1(PackedBits::pack(&encode(MiniFloatFormat::E2M1), 4))
+        }
+        other => unreachable!("{other} is not a float type"),
+    }
+}
+
+/// Builds the payload for an integer `to`; every value is already in range.
+fn ints_to_data(values: Vec<i128>, to: &DataType) -> TensorData {
+    match to {
+        DataType::Int64 => TensorData::Int64(values.iter().map(|&x| x as i64).collect()),
+        DataType::Int32 => TensorData::Int32(values.iter().map(|&x| x as i32).collect()),
+        DataType::Int16 => TensorData::Int16(values.iter().map(|&x| x as i16).collect()),
+        DataType::Int8 => TensorData::Int8(values.iter().map(|&x| x as i8).collect()),
+        DataType::Uint64 => TensorData::Uint64(values.iter().map(|&x| x as u64).collect()),
+        DataType::Uint32 => TensorData::Uint32(values.iter().map(|&x| x as u32).collect()),
+        DataType::Uint16 => TensorData::Uint16(values.iter().map(|&x| x as u16).collect()),
+        DataType::Uint8 => TensorData::Uint8(values.iter().map(|&x| x as u8).collect()),
+        DataType::Int4 | DataType::Int2 | DataType::Uint4 | DataType::Uint2 => {
+            let codes: Vec<u8> = values.iter().map(|&x| x as u8).collect();
+            let packed = PackedBits::pack(&codes, to.bit_width());
+            match to {
+                DataType::Int4 => TensorData::Int4(packed),
+                DataType::Int2 => TensorData::Int2(packed),
+                DataType::Uint4 => TensorData::Uint4(packed),
+                _ => TensorData::Uint2(packed),
+            }
+        }
+        other => unreachable!("{other} is not an integer type"),
+    }
+}
+
+fn int_range(to: &DataType) -> (i128, i128) {
+    let bits = to.bit_width() as u32;
+    if to.is_signed() {
+        (-(1 << (bits - 1)), (1 << (bits - 1)) - 1)
+    } else {
+        (0, (1 << bits) - 1)
+    }
+}
+
+fn wrap(x: i128, to: &DataType) -> i128 {
+    let bits = to.bit_width() as u32;
+    let wrapped = x.rem_euclid(1 << bits);
+    if to.is_signed() && wrapped >= 1 << (bits - 1) {
+        wrapped - (1 << bits)
+    } else {
+        wrapped
+    }
+}
@@ -0,0 +1,162 @@
+//! Element-wise comparison of two payloads, for checking WebNN results
+//! against reference outputs.
+
+use crate::error::{ConversionError, Result};
+use crate::half_float::HalfFormat;
+use crate::tensor_data::TensorData;
+
+/// How close two floating point elements must be. Integer and boolean
+/// elements always have to be equal.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Tolerance {
+    /// Equal values; `+0` and `-0` match.
+    #[default]
+    Exact,
+    /// `|actual - expected| <= absolute + relative * |expected|`.
+    Epsilon { absolute: f64, relative: f64 },
+    /// At most this many representable values of the element type apart,
+    /// counted on the bit patterns (so `Float16` steps are `Float16` ULPs).
+    Ulp(u64),
+}
+
+/// Result of [`TensorData::compare`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ComparisonReport {
+    pub count: usize,
+    /// Elements outside the tolerance, including NaN mismatches.
+    pub mismatch_count: usize,
+    /// Elements where exactly one side is NaN. Two NaNs match.
+    pub nan_mismatch_count: usize,
+    /// Largest error over the elements that are not NaN mismatches: the
+    /// absolute difference, or the distance in ULPs for [`Tolerance::Ulp`].
+    pub max_error: f64,
+    /// Index of the element with `max_error`, `None` if every element is
+    /// equal.
+    pub max_error_index: Option<usize>,
+    pub first_mismatch_index: Option<usize>,
+}
+
+impl ComparisonReport {
+    pub fn passed(&self) -> bool {
+        self.mismatch_count == 0
+    }
+}
+
+impl TensorData {
+    /// Compares `self` (the actual output) against `expected`, which must
+    /// have the same data type and element count.
+    pub fn compare(&self, expected: &TensorData, tolerance: Tolerance) -> Result<ComparisonReport> {
+        let dtype = self.data_type().ok_or_else(|| {
+            ConversionError::InvalidTensorData(
+                "cannot compare undecoded raw tensor data".to_string(),
+            )
+        })?;
+        if expected.data_type().as_ref() != Some(&dtype) || expected.count() != self.count() {
+            return Err(ConversionError::InvalidTensorData(format!(
+                "cannot compare {} {dtype} elements with {} {:?} elements",
+                self.count(),
+                expected.count(),
+                expected.data_type()
+            )));
+        }
+
+        let mut report = ComparisonReport {
+            count: self.count(),
+            ..Default::default()
+        };
+        let actual: Vec<f64> = self.iter_f64(None)?.collect();
+        let reference: Vec<f64> = expected.iter_f64(None)?.collect();
+        let ulps = match tolerance {
+            Tolerance::Ulp(_) if dtype.is_floating() => Some(ulp_distances(self, expected)),
+            _ => None,
+        };
+
+        for (i, (&a, &e)) in actual.iter().zip(&reference).enumerate() {
+            if a.is_nan() || e.is_nan() {
+                if a.is_nan() != e.is_nan() {
+                    report.nan_mismatch_count += 1;
+                    report.mismatch_count += 1;
+                    report.first_mismatch_index.get_or_insert(i);
+                }
+                continue;
+            }
+
+            let (error, within) = if !dtype.is_floating() {
+                // compare the integers themselves, f64 loses precision past 2^53
+                let equal = self.get(i) == expected.get(i);
+                (if equal { 0.0 } else { (a - e).abs().max(1.0) }, equal)
+            } else if a == e {
+                (0.0, true)
+            } else {
+                match (tolerance, &ulps) {
+                    (Tolerance::Exact, _) => ((a - e).abs(), false),
+                    (Tolerance::Epsilon { absolute, relative }, _) => {
+                        let error = (a - e).abs();
+                        (error, error <= absolute + relative * e.abs())
+                    }
+                    (Tolerance::Ulp(max), Some(ulps)) => (ulps[i] as f64, ulps[i] <= max as u128),
+                    (Tolerance::Ulp(_), None) => unreachable!("distances exist for floats"),
+                }
+            };
+
+            if error > report.max_error {
+                report.max_error = error;
+                report.max_error_index = Some(i);
+            }
+            if !within {
+                report.mismatch_count += 1;
+                report.first_mismatch_index.get_or_insert(i);
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// Distance in ULPs between corresponding float elements; 0 where either is
+/// NaN, which the caller handles separately.
+fn ulp_distances(a: &TensorData, b: &TensorData) -> Vec<u128> {
+    let half = |format: HalfFormat, x: &[u16], y: &[u16]| {
+        x.iter()
+            .zip(y)
+            .map(|(&p, &q)| format.ulp_distance(p, q).unwrap_or(0) as u128)
+            .collect()
+    };
+    match (a, b) {
+        (TensorData::Float16(x), TensorData::Float16(y)) => half(HalfFormat::F16, x, y),
+        (TensorData::BFloat16(x), TensorData::BFloat16(y)) => half(HalfFormat::BF16, x, y),
+        _ => ulp_positions(a)
+            .into_iter()
+            .zip(ulp_positions(b))
+            .map(|(p, q)| p.abs_diff(q))
+            .collect(),
+    }
+}
+
+/// Places the bit pattern of each float element on a signed line where
+/// neighbouring values differ by one and both zeros map to 0.
+fn ulp_positions(data: &TensorData) -> Vec<i128> {
+    fn signed(bits: u64, sign_bit: u64) -> i128 {
+        let magnitude = (bits & (sign_bit - 1)) as i128;
+        if bits & sign_bit != 0 {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+    let codes = |v: &[u8], sign_bit: u64| v.iter().map(|&c| signed(c as u64, sign_bit)).collect();
+    match data {
+        TensorData::Float32(v) => v
+            .iter()
+            .map(|x| signed(x.to_bits() as u64, 1 << 31))
+            .collect(),
+        TensorData::Float64(v) => v.iter().map(|x| signed(x.to_bits(), 1 << 63)).collect(),
+        TensorData::Float8E4M3FN(v)
+        | TensorData::Float8E4M3FNUZ(v)
+        | TensorData::Float8E5M2(v)
+        | TensorData::Float8E5M2FNUZ(v) => codes(v, 1 << 7),
+        // unsigned exponent only
+        TensorData::Float8E8M0(v) => codes(v, 1 << 8),
+        TensorData::Float4E2M1(p) => codes(&p.unpack(4), 1 << 3),
+        other => unreachable!("{:?} is not a float payload", other.data_type()),
+    }
+}
@@ -0,0 +1,701 @@
+//! Reference CPU evaluation of the ops that make up shape-computation
+//! subgraphs (`Shape` -> `Gather` -> `Unsqueeze` -> `Concat` -> `Reshape`).
+//!
+//! WebNN has no dynamic shapes, so these chains have to be folded into static
+//! initializers before emission. Only what shape arithmetic needs is
+//! supported; this is not a general ONNX runtime.
+
+use std::collections::HashMap;
+
+use crate::attributes::{AttrParser, require_attr};
+use crate::cast::CastMode;
+use crate::data_types::{DataType, onnx_to_webnn};
+use crate::error::{ConversionError, Result};
+use crate::protos::onnx::tensor_proto::DataLocation;
+use crate::protos::onnx::{NodeProto, TensorProto};
+use crate::shape_inference::{ShapeInferenceContext, TensorShape, broadcast_shapes};
+use crate::tensor::Tensor;
+use crate::tensor_data::{
+    TensorData, element_bytes, element_count_of, element_width, from_element_bytes,
+};
+
+/// Ops [`ConstantFolder`] can evaluate.
+pub const FOLDABLE_OPS: &[&str] = &[
+    "Constant",
+    "Shape",
+    "Gather",
+    "Concat",
+    "Slice",
+    "Unsqueeze",
+    "Squeeze",
+    "Cast",
+    "Add",
+    "Sub",
+    "Mul",
+    "Div",
+    "Range",
+    "ConstantOfShape",
+    "Equal",
+    "Where",
+];
+
+/// Evaluates nodes whose inputs are all known and records their outputs as
+/// constants.
+///
+/// `Shape` only needs the static shape of its input, which is taken from the
+/// [`ShapeInferenceContext`]; that is what lets a chain starting at a dynamic
+/// graph input fold once its dimensions are overridden.
+#[derive(Debug, Clone, Default)]
+pub struct ConstantFolder {
+    constants: HashMap<String, Tensor>,
+}
+
+impl ConstantFolder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts from the graph initializers. External and string initializers
+    /// are left out, so nodes reading them are not folded.
+    pub fn from_initializers(initializers: &[TensorProto]) -> Result<Self> {
+        let mut folder = Self::new();
+        for tensor in initializers {
+            if let Some(value) = load_tensor(tensor)? {
+                folder.insert(tensor.name.clone(), value);
+            }
+        }
+        Ok(folder)
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, tensor: Tensor) {
+        self.constants.insert(name.into(), tensor);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Tensor> {
+        self.constants.get(name)
+    }
+
+    /// The constant `name` as an initializer.
+    pub fn to_initializer(&self, name: &str) -> Option<TensorProto> {
+        self.get(name).map(|t| t.to_tensor_proto(name.to_string()))
+    }
+
+    /// Evaluates `node` if it is foldable and its inputs are known, storing
+    /// the outputs and setting their shapes in `ctx`. Returns whether the node
+    /// was folded.
+    pub fn fold_node(&mut self, node: &NodeProto, ctx: &mut ShapeInferenceContext) -> Result<bool> {
+        if !matches!(node.domain.as_str(), "" | "ai.onnx")
+            || !FOLDABLE_OPS.contains(&node.op_type.as_str())
+        {
+            return Ok(false);
+        }
+
+        let mut inputs = Vec::with_capacity(node.input.len());
+        for (i, name) in node.input.iter().enumerate() {
+            match self.constants.get(name) {
+                Some(tensor) => inputs.push(Some(tensor)),
+                // optional inputs are omitted with an empty name
+                None if name.is_empty() => inputs.push(None),
+                None if node.op_type == "Shape" && i == 0 => inputs.push(None),
+                None => return Ok(false),
+            }
+        }
+
+        let Some(outputs) = evaluate(node, &inputs, ctx)? else {
+            return Ok(false);
+        };
+        for (name, tensor) in node.output.iter().zip(outputs) {
+            ctx.set_shape(
+                name.clone(),
+                TensorShape::from_static(tensor.shape().to_vec()),
+            );
+            self.constants.insert(name.clone(), tensor);
+        }
+        Ok(true)
+    }
+
+    /// Folds `nodes` in order, which must be topological as in a
+    /// `GraphProto`. Returns the indices of the folded nodes.
+    pub fn fold_nodes(
+        &mut self,
+        nodes: &[NodeProto],
+        ctx: &mut ShapeInferenceContext,
+    ) -> Result<Vec<usize>> {
+        let mut folded = vec![];
+        for (i, node) in nodes.iter().enumerate() {
+            if self.fold_node(node, ctx)? {
+                folded.push(i);
+            }
+        }
+        Ok(folded)
+    }
+}
+
+/// Evaluates one node. `inputs` holds the constant value of each input, or
+/// `None` for omitted optional inputs (and for the input of `Shape`, which is
+/// read from `ctx`). Returns `None` if the node cannot be folded, including
+/// for values this evaluator does not represent (strings, sparse constants,
+/// `Uint64` past `i64::MAX`); errors are for malformed nodes.
+pub fn evaluate(
+    node: &NodeProto,
+    inputs: &[Option<&Tensor>],
+    ctx: &ShapeInferenceContext,
+) -> Result<Option<Vec<Tensor>>> {
+    let attrs = AttrParser::new(&node.attribute);
+    let input = |i: usize| {
+        inputs.get(i).copied().flatten().ok_or_else(|| {
+            ConversionError::InvalidTensorData(format!(
+                "{} node '{}' is missing input {i}",
+                node.op_type, node.name
+            ))
+        })
+    };
+
+    let output = match node.op_type.as_str() {
+        "Constant" => match constant(&attrs)? {
+            Some(tensor) => tensor,
+            None => return Ok(None),
+        },
+        "Shape" => {
+            let shape = match inputs.first().copied().flatten() {
+                Some(tensor) => tensor.shape().to_vec(),
+                None => match node
+                    .input
+                    .first()
+                    .and_then(|name| ctx.get_static_shape(name))
+                {
+                    Some(shape) => shape,
+                    None => return Ok(None),
+                },
+            };
+            let rank = shape.len() as i64;
+            let clamp = |v: i64| (if v < 0 { v + rank } else { v }).clamp(0, rank) as usize;
+            let start = clamp(attrs.get_int("start").unwrap_or(0));
+            let end = clamp(attrs.get_int("end").unwrap_or(rank));
+            let dims = shape[start..end.max(start)].to_vec();
+            int64_tensor(vec![dims.len() as i64], dims)?
+        }
+        "Gather" => gather(input(0)?, input(1)?, attrs.get_int("axis").unwrap_or(0))?,
+        "Concat" => {
+            let tensors = (0..inputs.len()).map(input).collect::<Result<Vec<_>>>()?;
+            concat(&tensors, require_attr("axis", attrs.get_int("axis"))?)?
+        }
+        "Slice" => {
+            let optional = |i: usize| inputs.get(i).copied().flatten().map(to_i64s).transpose();
+            slice(
+                input(0)?,
+                &to_i64s(input(1)?)?,
+                &to_i64s(input(2)?)?,
+                optional(3)?,
+                optional(4)?,
+            )?
+        }
+        "Unsqueeze" => {
+            let axes = match inputs.get(1).copied().flatten() {
+                Some(axes) => to_i64s(axes)?,
+                None => require_attr("axes", attrs.get_ints("axes"))?,
+            };
+            unsqueeze(input(0)?, &axes)?
+        }
+        "Squeeze" => {
+            let axes = match inputs.get(1).copied().flatten() {
+                Some(axes) => Some(to_i64s(axes)?),
+                None => attrs.get_ints("axes"),
+            };
+            squeeze(input(0)?, axes.as_deref())?
+        }
+        "Cast" => {
+            let Ok(to) = onnx_to_webnn(require_attr("to", attrs.get_int("to"))? as i32) else {
+                return Ok(None);
+            };
+            // saturate (default 1) only applies to float8 targets
+            let mode = if to.is_floating()
+                && to.bit_width() <= 8
+                && attrs.get_int("saturate") != Some(0)
+            {
+                CastMode::Saturate
+            } else {
+                CastMode::Wrap
+            };
+            let x = input(0)?;
+            Tensor::new(
+                to.clone(),
+                x.shape().to_vec(),
+                x.data().cast(to, mode)?.data,
+            )?
+        }
+        // integer arithmetic runs on i64
+        "Add" | "Sub" | "Mul" | "Div" | "Equal" | "Range"
+            if inputs.iter().flatten().any(|t| exceeds_i64(t)) =>
+        {
+            return Ok(None);
+        }
+        op @ ("Add" | "Sub" | "Mul" | "Div") => arithmetic(op, input(0)?, input(1)?)?,
+        "Equal" => equal(input(0)?, input(1)?)?,
+        "Where" => where_(input(0)?, input(1)?, input(2)?)?,
+        "Range" => range(input(0)?, input(1)?, input(2)?)?,
+        "ConstantOfShape" => {
+            let shape = to_i64s(input(0)?)?;
+            let value = match attrs.get_tensor("value").map(load_tensor).transpose()? {
+                Some(Some(value)) => value,
+                Some(None) => return Ok(None),
+                None => Tensor::scalar(DataType::Float32, 0.0),
+            };
+            if value.element_count() != 1 {
+                return Err(ConversionError::InvalidAttribute(
+                    "ConstantOfShape value must have one element".to_string(),
+                ));
+            }
+            let numel = element_count_of(&shape)?;
+            value.take(&vec![0; numel], shape)?
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(vec![output]))
+}
+
+fn constant(attrs: &AttrParser) -> Result<Option<Tensor>> {
+    if let Some(value) = attrs.get_tensor("value") {
+        return load_tensor(value);
+    }
+    let tensor = if let Some(v) = attrs.get_int("value_int") {
+        int64_tensor(vec![], vec![v])?
+    } else if let Some(v) = attrs.get_ints("value_ints") {
+        int64_tensor(vec![v.len() as i64], v)?
+    } else if let Some(v) = attrs.get_float("value_float") {
+        Tensor::new(DataType::Float32, vec![], TensorData::Float32(vec![v]))?
+    } else if let Some(v) = attrs.get_floats("value_floats") {
+        Tensor::new(
+            DataType::Float32,
+            vec![v.len() as i64],
+            TensorData::Float32(v),
+        )?
+    } else if ["sparse_value", "value_string", "value_strings"]
+        .iter()
+        .any(|name| attrs.has(name))
+    {
+        return Ok(None);
+    } else {
+        return Err(ConversionError::InvalidAttribute(
+            "Constant needs a value, sparse_value, value_int(s), value_float(s) or \
+             value_string(s) attribute"
+                .to_string(),
+        ));
+    };
+    Ok(Some(tensor))
+}
+
+/// Loads `tensor`, or returns `None` if its values cannot be held in memory
+/// here: external data and element types without a WebNN counterpart.
+fn load_tensor(tensor: &TensorProto) -> Result<Option<Tensor>> {
+    if tensor.data_location == DataLocation::External as i32
+        || onnx_to_webnn(tensor.data_type).is_err()
+    {
+        return Ok(None);
+    }
+    Tensor::from_tensor_proto(tensor).map(Some)
+}
+
+fn exceeds_i64(tensor: &Tensor) -> bool {
+    matches!(tensor.data(), TensorData::Uint64(v) if v.iter().any(|&x| x > i64::MAX as u64))
+}
+
+fn gather(data: &Tensor, indices: &Tensor, axis: i64) -> Result<Tensor> {
+    let axis = normalize_axis(axis, data.rank())?;
+    let dim = data.shape()[axis];
+    let outer = element_count_of(&data.shape()[..axis])?;
+    let inner = element_count_of(&data.shape()[axis + 1..])?;
+
+    let positions = to_i64s(indices)?
+        .into_iter()
+        .map(|i| {
+            let k = if i < 0 { i + dim } else { i };
+            if (0..dim).contains(&k) {
+                Ok(k as usize)
+            } else {
+                Err(ConversionError::InvalidTensorData(format!(
+                    "Gather index {i} is out of bounds for axis of size {dim}"
+                )))
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut flat = Vec::with_capacity(outer * positions.len() * inner);
+    for o in 0..outer {
+        for &k in &positions {
+            let base = (o * dim as usize + k) * inner;
+            flat.extend(base..base + inner);
+        }
+    }
+
+    let mut shape = data.shape()[..axis].to_vec();
+    shape.extend_from_slice(indices.shape());
+    shape.extend_from_slice(&data.shape()[axis + 1..]);
+    data.take(&flat, shape)
+}
+
+fn concat(tensors: &[&Tensor], axis: i64) -> Result<Tensor> {
+    let first = tensors.first().ok_or_else(|| {
+        ConversionError::InvalidTensorData("Concat needs at least one input".to_string())
+    })?;
+    let axis = normalize_axis(axis, first.rank())?;
+    let dtype = first.dtype();
+    let mut shape = first.shape().to_vec();
+    shape[axis] = 0;
+    for t in tensors {
+        let mut other = t.shape().to_vec();
+        if t.dtype() != dtype || other.len() != shape.len() {
+            return Err(mismatch("Concat", tensors));
+        }
+        shape[axis] += other[axis];
+        other[axis] = shape[axis];
+        if other != shape {
+            return Err(mismatch("Concat", tensors));
+        }
+    }
+
+    let width = element_width(dtype);
+    let outer = element_count_of(&shape[..axis])?;
+    let parts = tensors
+        .iter()
+        .map(|t| {
+            let row = element_count_of(&t.shape()[axis..])? * width;
+            Ok((element_bytes(t.data(), dtype, t.element_count())?, row))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut bytes = Vec::with_capacity(parts.iter().map(|(b, _)| b.len()).sum());
+    for o in 0..outer {
+        for (part, row) in &parts {
+            bytes.extend_from_slice(&part[o * row..(o + 1) * row]);
+        }
+    }
+    Tensor::new(dtype.clone(), shape, from_element_bytes(&bytes, dtype))
+}
+
+fn slice(
+    data: &Tensor,
+    starts: &[i64],
+    ends: &[i64],
+    axes: Option<Vec<i64>>,
+    steps: Option<Vec<i64>>,
+) -> Result<Tensor> {
+    let rank = data.rank();
+    let axes = axes.unwrap_or_else(|| (0..starts.len() as i64).collect());
+    let steps = steps.unwrap_or_else(|| vec![1; starts.len()]);
+    if ends.len() != starts.len() || axes.len() != starts.len() || steps.len() != starts.len() {
+        return Err(ConversionError::InvalidTensorData(
+            "Slice starts, ends, axes and steps must have the same length".to_string(),
+        ));
+    }
+
+    // selected coordinates along each dimension
+    let mut selected: Vec<Vec<usize>> = data
+        .shape()
+        .iter()
+        .map(|&d| (0..d as usize).collect())
+        .collect();
+    for (((&start, &end), &axis), &step) in starts.iter().zip(ends).zip(&axes).zip(&steps) {
+        let axis = normalize_axis(axis, rank)?;
+        let dim = data.shape()[axis];
+        let resolve = |v: i64| if v < 0 { v.saturating_add(dim) } else { v };
+        selected[axis] = match step {
+            0 => {
+                return Err(ConversionError::InvalidTensorData(
+                    "Slice step cannot be 0".to_string(),
+                ));
+            }
+            s if s > 0 => {
+                let start = resolve(start).clamp(0, dim);
+                let end = resolve(end).clamp(0, dim);
+                (start..end)
+                    .step_by(s as usize)
+                    .map(|i| i as usize)
+                    .collect()
+            }
+            _ if dim == 0 => vec![],
+            s => {
+                let start = resolve(start).clamp(0, dim - 1);
+                let end = resolve(end).clamp(-1, dim - 1);
+                let mut coords = vec![];
+                let mut i = start;
+                while i > end {
+                    coords.push(i as usize);
+                    i += s;
+                }
+                coords
+            }
+        };
+    }
+
+    let shape: Vec<i64> = selected.iter().map(|c| c.len() as i64).collect();
+    let strides = data.strides();
+    let mut flat = vec![0usize];
+    for (coords, stride) in selected.iter().zip(strides) {
+        flat = flat
+            .iter()
+            .flat_map(|&base| coords.iter().map(move |&c| base + c * stride))
+            .collect();
+    }
+    data.take(&flat, shape)
+}
+
+fn unsqueeze(data: &Tensor, axes: &[i64]) -> Result<Tensor> {
+    let rank = data.rank() + axes.len();
+    let mut axes = axes
+        .iter()
+        .map(|&a| normalize_axis(a, rank))
+        .collect::<Result<Vec<_>>>()?;
+    axes.sort_unstable();
+    if axes.windows(2).any(|w| w[0] == w[1]) {
+        return Err(ConversionError::InvalidTensorData(format!(
+            "Unsqueeze axes {axes:?} contain duplicates"
+        )));
+    }
+    let mut dims = data.shape().iter();
+    let shape: Vec<i64> = (0..rank)
+        .map(|i| {
+            if axes.contains(&i) {
+                1
+            } else {
+                *dims.next().expect("rank accounts for every input dim")
+            }
+        })
+        .collect();
+    data.clone().reshape(&shape)
+}
+
+fn squeeze(data: &Tensor, axes: Option<&[i64]>) -> Result<Tensor> {
+    let axes = match axes {
+        Some(axes) => axes
+            .iter()
+            .map(|&a| {
+                let axis = normalize_axis(a, data.rank())?;
+                if data.shape()[axis] != 1 {
+                    return Err(ConversionError::InvalidTensorData(format!(
+                        "cannot squeeze axis {a} of shape {:?}",
+                        data.shape()
+                    )));
+                }
+                Ok(axis)
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => (0..data.rank()).filter(|&i| data.shape()[i] == 1).collect(),
+    };
+    let shape: Vec<i64> = data
+        .shape()
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !axes.contains(i))
+        .map(|(_, &d)| d)
+        .collect();
+    data.clone().reshape(&shape)
+}
+
+fn arithmetic(op: &str, a: &Tensor, b: &Tensor) -> Result<Tensor> {
+    if a.dtype() != b.dtype() {
+        return Err(mismatch(op, &[a, b]));
+    }
+    let dtype = a.dtype();
+    if *dtype == DataType::Bool {
+        return Err(ConversionError::UnsupportedDataType(format!(
+            "{op} does not support bool"
+        )));
+    }
+    let (shape, ia, ib) = broadcast(op, a, b)?;
+    let data = match (Values::of(a)?, Values::of(b)?) {
+        (Values::Float(x), Values::Float(y)) => {
+            let f = match op {
+                "Add" => |p: f64, q: f64| p + q,
+                "Sub" => |p: f64, q: f64| p - q,
+                "Mul" => |p: f64, q: f64| p * q,
+                _ => |p: f64, q: f64| p / q,
+            };
+            let v = ia.iter().zip(&ib).map(|(&i, &j)| f(x[i], y[j])).collect();
+            TensorData::Float64(v)
+        }
+        (Values::Int(x), Values::Int(y)) => {
+            let v = ia
+                .iter()
+                .zip(&ib)
+                .map(|(&i, &j)| match op {
+                    "Add" => Ok(x[i].wrapping_add(y[j])),
+                    "Sub" => Ok(x[i].wrapping_sub(y[j])),
+                    "Mul" => Ok(x[i].wrapping_mul(y[j])),
+                    _ if y[j] == 0 => Err(ConversionError::InvalidTensorData(
+                        "integer division by zero".to_string(),
+                    )),
+                    _ => Ok(x[i].wrapping_div(y[j])),
+                })
+                .collect::<Result<_>>()?;
+            TensorData::Int64(v)
+        }
+        _ => unreachable!("inputs have the same dtype"),
+    };
+    Tensor::new(
+        dtype.clone(),
+        shape,
+        data.cast(dtype.clone(), CastMode::Wrap)?.data,
+    )
+}
+
+fn equal(a: &Tensor, b: &Tensor) -> Result<Tensor> {
+    if a.dtype() != b.dtype() {
+        return Err(mismatch("Equal", &[a, b]));
+    }
+    let (shape, ia, ib) = broadcast("Equal", a, b)?;
+    let v = match (Values::of(a)?, Values::of(b)?) {
+        (Values::Float(x), Values::Float(y)) => {
+            ia.iter().zip(&ib).map(|(&i, &j)| x[i] == y[j]).collect()
+        }
+        (Values::Int(x), Values::Int(y)) => {
+            ia.iter().zip(&ib).map(|(&i, &j)| x[i] == y[j]).collect()
+        }
+        _ => unreachable!("inputs have the same dtype"),
+    };
+    Tensor::new(DataType::Bool, shape, TensorData::Bool(v))
+}
+
+fn where_(condition: &Tensor, x: &Tensor, y: &Tensor) -> Result<Tensor> {
+    if condition.dtype() != &DataType::Bool || x.dtype() != y.dtype() {
+        return Err(mismatch("Where", &[condition, x, y]));
+    }
+    let shape = broadcast_shapes(condition.shape(), x.shape())
+        .and_then(|s| broadcast_shapes(&s, y.shape()))
+        .ok_or_else(|| mismatch("Where", &[condition, x, y]))?;
+    let cond = to_i64s(condition)?;
+    let ic = broadcast_indices(&shape, condition.shape());
+    let ix = broadcast_indices(&shape, x.shape());
+    let iy = broadcast_indices(&shape, y.shape());
+
+    let dtype = x.dtype();
+    let width = element_width(dtype);
+    let bx = element_bytes(x.data(), dtype, x.element_count())?;
+    let by = element_bytes(y.data(), dtype, y.element_count())?;
+    let mut bytes = Vec::with_capacity(ic.len() * width);
+    for ((&c, &i), &j) in ic.iter().zip(&ix).zip(&iy) {
+        let (src, k) = if cond[c] != 0 { (&bx, i) } else { (&by, j) };
+        bytes.extend_from_slice(&src[k * width..(k + 1) * width]);
+    }
+    Tensor::new(dtype.clone(), shape, from_element_bytes(&bytes, dtype))
+}
+
+fn range(start: &Tensor, limit: &Tensor, delta: &Tensor) -> Result<Tensor> {
+    let dtype = start.dtype();
+    if limit.dtype() != dtype || delta.dtype() != dtype {
+        return Err(mismatch("Range", &[start, limit, delta]));
+    }
+    if [start, limit, delta].iter().any(|t| t.element_count() != 1) {
+        return Err(ConversionError::InvalidTensorData(
+            "Range inputs must be scalars".to_string(),
+        ));
+    }
+
+    let data = match (Values::of(start)?, Values::of(limit)?, Values::of(delta)?) {
+        (Values::Float(s), Values::Float(l), Values::Float(d)) => {
+            let (s, l, d) = (s[0], l[0], d[0]);
+            if d == 0.0 {
+                return Err(ConversionError::InvalidTensorData(
+                    "Range delta is 0".to_string(),
+                ));
+            }
+            let n = ((l - s) / d).ceil().max(0.0) as usize;
+            TensorData::Float64((0..n).map(|i| s + i as f64 * d).collect())
+        }
+        (Values::Int(s), Values::Int(l), Values::Int(d)) => {
+            let (s, l, d) = (s[0] as i128, l[0] as i128, d[0] as i128);
+            if d == 0 {
+                return Err(ConversionError::InvalidTensorData(
+                    "Range delta is 0".to_string(),
+                ));
+            }
+            // ceil((l - s) / d) for either sign of d
+            let n = ((l - s + d - d.signum()) / d).max(0) as usize;
+            TensorData::Int64((0..n).map(|i| (s + i as i128 * d) as i64).collect())
+        }
+        _ => unreachable!("inputs have the same dtype"),
+    };
+    let len = data.count() as i64;
+    Tensor::new(
+        dtype.clone(),
+        vec![len],
+        data.cast(dtype.clone(), CastMode::Wrap)?.data,
+    )
+}
+
+/// Element values in a type that can do the arithmetic; bools count as ints.
+enum Values {
+    Float(Vec<f64>),
+    Int(Vec<i64>),
+}
+
+impl Values {
+    fn of(tensor: &Tensor) -> Result<Self> {
+        if tensor.dtype().is_floating() {
+            Ok(Values::Float(tensor.data().iter_f64(None)?.collect()))
+        } else {
+            Ok(Values::Int(to_i64s(tensor)?))
+        }
+    }
+}
+
+fn to_i64s(tensor: &Tensor) -> Result<Vec<i64>> {
+    tensor.data().to_vec_i64()
+}
+
+fn int64_tensor(shape: Vec<i64>, values: Vec<i64>) -> Result<Tensor> {
+    Tensor::new(DataType::Int64, shape, TensorData::Int64(values))
+}
+
+/// Output shape of a broadcast binary op and, per output element, the flat
+/// index into each input.
+fn broadcast(op: &str, a: &Tensor, b: &Tensor) -> Result<(Vec<i64>, Vec<usize>, Vec<usize>)> {
+    let shape = broadcast_shapes(a.shape(), b.shape()).ok_or_else(|| mismatch(op, &[a, b]))?;
+    let ia = broadcast_indices(&shape, a.shape());
+    let ib = broadcast_indices(&shape, b.shape());
+    Ok((shape, ia, ib))
+}
+
+/// Flat index into an input of `shape` for each element of `out_shape`.
+fn broadcast_indices(out_shape: &[i64], shape: &[i64]) -> Vec<usize> {
+    let offset = out_shape.len() - shape.len();
+    // stride 0 along broadcast dimensions
+    let mut strides = vec![0usize; out_shape.len()];
+    let mut stride = 1usize;
+    for (i, &d) in shape.iter().enumerate().rev() {
+        if d != 1 {
+            strides[offset + i] = stride;
+        }
+        stride *= d as usize;
+    }
+
+    let mut indices = vec![0usize];
+    for (&d, &s) in out_shape.iter().zip(&strides) {
+        indices = indices
+            .iter()
+            .flat_map(|&base| (0..d as usize).map(move |c| base + c * s))
+            .collect();
+    }
+    indices
+}
+
+fn normalize_axis(axis: i64, rank: usize) -> Result<usize> {
+    let rank = rank as i64;
+    let normalized = if axis < 0 { axis + rank } else { axis };
+    if (0..rank).contains(&normalized) {
+        Ok(normalized as usize)
+    } else {
+        Err(ConversionError::InvalidAttribute(format!(
+            "axis {axis} is out of range for rank {rank}"
+        )))
+    }
+}
+
+fn mismatch(op: &str, inputs: &[&Tensor]) -> ConversionError {
+    let described: Vec<String> = inputs
+        .iter()
+        .map(|t| format!("{} {:?}", t.dtype(), t.shape()))
+        .collect();
+    ConversionError::InvalidTensorData(format!(
+        "{op} inputs are incompatible: {}",
+        described.join(", ")
+    ))
+}
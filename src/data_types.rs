@@ -1,18 +1,261 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
 use crate::error::{ConversionError, Result};
 use crate::protos::onnx::tensor_proto::DataType as ProtoDataType;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Element types understood by this crate.
+///
+/// The first ten variants are native WebNN operand types. `Bool`, `Int16`,
+/// `Uint16`, `Float64`, `BFloat16`, `Int2`, `Uint2` and the 8-bit and 4-bit
+/// float formats only exist on the ONNX side; see
+/// [`webnn_lowering`] for the type each of them is converted to when emitting
+/// a WebNN graph.
+///
+/// Serializes with the WebNN spelling (`"float32"`); the ONNX
+/// `TensorProto.DataType` names (`"FLOAT"`, `"INT64"`) are accepted on input.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DataType {
+    #[serde(alias = "FLOAT")]
     Float32,
+    #[serde(alias = "FLOAT16")]
     Float16,
+    #[serde(alias = "INT32")]
     Int32,
+    #[serde(alias = "UINT32")]
     Uint32,
+    #[serde(alias = "INT64")]
     Int64,
+    #[serde(alias = "UINT64")]
     Uint64,
+    #[serde(alias = "INT8")]
     Int8,
+    #[serde(alias = "UINT8")]
     Uint8,
+    #[serde(alias = "INT4")]
+    Int4,
+    #[serde(alias = "UINT4")]
+    Uint4,
+    #[serde(alias = "BOOL")]
+    Bool,
+    #[serde(alias = "INT16")]
+    Int16,
+    #[serde(alias = "UINT16")]
+    Uint16,
+    #[serde(alias = "DOUBLE")]
+    Float64,
+    #[serde(alias = "BFLOAT16")]
+    BFloat16,
+    #[serde(alias = "FLOAT8E4M3FN")]
+    Float8E4M3FN,
+    #[serde(alias = "FLOAT8E4M3FNUZ")]
+    Float8E4M3FNUZ,
+    #[serde(alias = "FLOAT8E5M2")]
+    Float8E5M2,
+    #[serde(alias = "FLOAT8E5M2FNUZ")]
+    Float8E5M2FNUZ,
+    #[serde(alias = "FLOAT8E8M0")]
+    Float8E8M0,
+    #[serde(alias = "FLOAT4E2M1")]
+    Float4E2M1,
+    #[serde(alias = "INT2")]
+    Int2,
+    #[serde(alias = "UINT2")]
+    Uint2,
+}
+
+impl DataType {
+    /// Number of bits occupied by one element.
+    pub fn bit_width(&self) -> usize {
+        match self {
+            DataType::Int2 | DataType::Uint2 => 2,
+            DataType::Int4 | DataType::Uint4 | DataType::Float4E2M1 => 4,
+            DataType::Int8
+            | DataType::Uint8
+            | DataType::Bool
+            | DataType::Float8E4M3FN
+            | DataType::Float8E4M3FNUZ
+            | DataType::Float8E5M2
+            | DataType::Float8E5M2FNUZ
+            | DataType::Float8E8M0 => 8,
+            DataType::Float16 | DataType::BFloat16 | DataType::Int16 | DataType::Uint16 => 16,
+            DataType::Float32 | DataType::Int32 | DataType::Uint32 => 32,
+            DataType::Float64 | DataType::Int64 | DataType::Uint64 => 64,
+        }
+    }
+
+    /// Size in bytes of one element, rounded up for sub-byte types.
+    pub fn byte_size(&self) -> usize {
+        self.bit_width().div_ceil(8)
+    }
+
+    /// Number of bytes needed to store `element_count` packed elements.
+    pub fn storage_len(&self, element_count: usize) -> usize {
+        (element_count * self.bit_width()).div_ceil(8)
+    }
+
+    pub fn is_floating(&self) -> bool {
+        matches!(
+            self,
+            DataType::Float32
+                | DataType::Float16
+                | DataType::Float64
+                | DataType::BFloat16
+                | DataType::Float8E4M3FN
+                | DataType::Float8E4M3FNUZ
+                | DataType::Float8E5M2
+                | DataType::Float8E5M2FNUZ
+                | DataType::Float8E8M0
+                | DataType::Float4E2M1
+        )
+    }
+
+    pub fn is_integer(&self) -> bool {
+        !self.is_floating() && *self != DataType::Bool
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            DataType::Int2
+                | DataType::Int4
+                | DataType::Int8
+                | DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+        ) || (self.is_floating() && *self != DataType::Float8E8M0)
+    }
+
+    /// Smallest finite value representable by the type.
+    pub fn min_value(&self) -> f64 {
+        match self {
+            DataType::Float32 => f32::MIN as f64,
+            DataType::Float16 => half::f16::MIN.to_f64(),
+            DataType::BFloat16 => half::bf16::MIN.to_f64(),
+            DataType::Float64 => f64::MIN,
+            DataType::Float8E8M0 => 2f64.powi(-127),
+            DataType::Float8E4M3FN
+            | DataType::Float8E4M3FNUZ
+            | DataType::Float8E5M2
+            | DataType::Float8E5M2FNUZ
+            | DataType::Float4E2M1 => -self.max_value(),
+            DataType::Int2 => -2.0,
+            DataType::Int4 => -8.0,
+            DataType::Int8 => i8::MIN as f64,
+            DataType::Int16 => i16::MIN as f64,
+            DataType::Int32 => i32::MIN as f64,
+            DataType::Int64 => i64::MIN as f64,
+            DataType::Uint2
+            | DataType::Uint4
+            | DataType::Uint8
+            | DataType::Uint16
+            | DataType::Uint32
+            | DataType::Uint64
+            | DataType::Bool => 0.0,
+        }
+    }
+
+    /// Largest finite value representable by the type.
+    pub fn max_value(&self) -> f64 {
+        match self {
+            DataType::Float32 => f32::MAX as f64,
+            DataType::Float16 => half::f16::MAX.to_f64(),
+            DataType::BFloat16 => half::bf16::MAX.to_f64(),
+            DataType::Float64 => f64::MAX,
+            DataType::Float8E4M3FN => 448.0,
+            DataType::Float8E4M3FNUZ => 240.0,
+            DataType::Float8E5M2 | DataType::Float8E5M2FNUZ => 57344.0,
+            DataType::Float8E8M0 => 2f64.powi(127),
+            DataType::Float4E2M1 => 6.0,
+            DataType::Int2 => 1.0,
+            DataType::Uint2 => 3.0,
+            DataType::Int4 => 7.0,
+            DataType::Int8 => i8::MAX as f64,
+            DataType::Int16 => i16::MAX as f64,
+            DataType::Int32 => i32::MAX as f64,
+            DataType::Int64 => i64::MAX as f64,
+            DataType::Uint4 => 15.0,
+            DataType::Uint8 => u8::MAX as f64,
+            DataType::Uint16 => u16::MAX as f64,
+            DataType::Uint32 => u32::MAX as f64,
+            DataType::Uint64 => u64::MAX as f64,
+            DataType::Bool => 1.0,
+        }
+    }
+
+    /// The WebNN `MLOperandDataType` spelling, e.g. `"float32"`.
+    ///
+    /// ONNX-only types use the same lowercase scheme (`"bool"`, `"float64"`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            DataType::Float32 => "float32",
+            DataType::Float16 => "float16",
+            DataType::Int32 => "int32",
+            DataType::Uint32 => "uint32",
+            DataType::Int64 => "int64",
+            DataType::Uint64 => "uint64",
+            DataType::Int8 => "int8",
+            DataType::Uint8 => "uint8",
+            DataType::Int4 => "int4",
+            DataType::Uint4 => "uint4",
+            DataType::Bool => "bool",
+            DataType::Int16 => "int16",
+            DataType::Uint16 => "uint16",
+            DataType::Float64 => "float64",
+            DataType::BFloat16 => "bfloat16",
+            DataType::Float8E4M3FN => "float8e4m3fn",
+            DataType::Float8E4M3FNUZ => "float8e4m3fnuz",
+            DataType::Float8E5M2 => "float8e5m2",
+            DataType::Float8E5M2FNUZ => "float8e5m2fnuz",
+            DataType::Float8E8M0 => "float8e8m0",
+            DataType::Float4E2M1 => "float4e2m1",
+            DataType::Int2 => "int2",
+            DataType::Uint2 => "uint2",
+        }
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for DataType {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "float32" => Ok(DataType::Float32),
+            "float16" => Ok(DataType::Float16),
+            "int32" => Ok(DataType::Int32),
+            "uint32" => Ok(DataType::Uint32),
+            "int64" => Ok(DataType::Int64),
+            "uint64" => Ok(DataType::Uint64),
+            "int8" => Ok(DataType::Int8),
+            "uint8" => Ok(DataType::Uint8),
+            "int4" => Ok(DataType::Int4),
+            "uint4" => Ok(DataType::Uint4),
+            "bool" => Ok(DataType::Bool),
+            "int16" => Ok(DataType::Int16),
+            "uint16" => Ok(DataType::Uint16),
+            "float64" => Ok(DataType::Float64),
+            "bfloat16" => Ok(DataType::BFloat16),
+            "float8e4m3fn" => Ok(DataType::Float8E4M3FN),
+            "float8e4m3fnuz" => Ok(DataType::Float8E4M3FNUZ),
+            "float8e5m2" => Ok(DataType::Float8E5M2),
+            "float8e5m2fnuz" => Ok(DataType::Float8E5M2FNUZ),
+            "float8e8m0" => Ok(DataType::Float8E8M0),
+            "float4e2m1" => Ok(DataType::Float4E2M1),
+            "int2" => Ok(DataType::Int2),
+            "uint2" => Ok(DataType::Uint2),
+            other => Err(ConversionError::UnsupportedDataType(other.to_string())),
+        }
+    }
 }
 
 pub fn webnn_to_onnx(webnn_type: DataType) -> ProtoDataType {
@@ -25,6 +268,21 @@ pub fn webnn_to_onnx(webnn_type: DataType) -> ProtoDataType {
         DataType::Uint64 => ProtoDataType::Uint64,
         DataType::Int8 => ProtoDataType::Int8,
         DataType::Uint8 => ProtoDataType::Uint8,
+        DataType::Int4 => ProtoDataType::Int4,
+        DataType::Uint4 => ProtoDataType::Uint4,
+        DataType::Bool => ProtoDataType::Bool,
+        DataType::Int16 => ProtoDataType::Int16,
+        DataType::Uint16 => ProtoDataType::Uint16,
+        DataType::Float64 => ProtoDataType::Double,
+        DataType::BFloat16 => ProtoDataType::Bfloat16,
+        DataType::Float8E4M3FN => ProtoDataType::Float8e4m3fn,
+        DataType::Float8E4M3FNUZ => ProtoDataType::Float8e4m3fnuz,
+        DataType::Float8E5M2 => ProtoDataType::Float8e5m2,
+        DataType::Float8E5M2FNUZ => ProtoDataType::Float8e5m2fnuz,
+        DataType::Float8E8M0 => ProtoDataType::Float8e8m0,
+        DataType::Float4E2M1 => ProtoDataType::Float4e2m1,
+        DataType::Int2 => ProtoDataType::Int2,
+        DataType::Uint2 => ProtoDataType::Uint2,
     }
 }
 
@@ -45,6 +303,173 @@ pub fn onnx_proto_to_webnn(proto_type: ProtoDataType) -> Result<DataType> {
         ProtoDataType::Uint64 => Ok(DataType::Uint64),
         ProtoDataType::Int8 => Ok(DataType::Int8),
         ProtoDataType::Uint8 => Ok(DataType::Uint8),
+        ProtoDataType::Int4 => Ok(DataType::Int4),
+        ProtoDataType::Uint4 => Ok(DataType::Uint4),
+        ProtoDataType::Bool => Ok(DataType::Bool),
+        ProtoDataType::Int16 => Ok(DataType::Int16),
+        ProtoDataType::Uint16 => Ok(DataType::Uint16),
+        ProtoDataType::Double => Ok(DataType::Float64),
+        ProtoDataType::Bfloat16 => Ok(DataType::BFloat16),
+        ProtoDataType::Float8e4m3fn => Ok(DataType::Float8E4M3FN),
+        ProtoDataType::Float8e4m3fnuz => Ok(DataType::Float8E4M3FNUZ),
+        ProtoDataType::Float8e5m2 => Ok(DataType::Float8E5M2),
+        ProtoDataType::Float8e5m2fnuz => Ok(DataType::Float8E5M2FNUZ),
+        ProtoDataType::Float8e8m0 => Ok(DataType::Float8E8M0),
+        ProtoDataType::Float4e2m1 => Ok(DataType::Float4E2M1),
+        ProtoDataType::Int2 => Ok(DataType::Int2),
+        ProtoDataType::Uint2 => Ok(DataType::Uint2),
         other => Err(ConversionError::UnsupportedOnnxDataType(other as i32)),
     }
 }
+
+/// Returns true if `dtype` is a native WebNN operand type.
+pub fn is_webnn_native(dtype: &DataType) -> bool {
+    matches!(
+        dtype,
+        DataType::Float32
+            | DataType::Float16
+            | DataType::Int32
+            | DataType::Uint32
+            | DataType::Int64
+            | DataType::Uint64
+            | DataType::Int8
+            | DataType::Uint8
+            | DataType::Int4
+            | DataType::Uint4
+    )
+}
+
+/// Returns the WebNN operand type used to represent `dtype`.
+///
+/// Native WebNN types map to themselves. The ONNX-only types are lowered as
+/// follows:
+///
+/// - `Bool` -> `Uint8` (0 or 1, matching WebNN logical operator outputs)
+/// - `Int16` -> `Int32` and `Uint16` -> `Uint32` (lossless widening)
+/// - `Float64` -> `Float32` (precision loss, values outside the f32 range
+///   become infinities)
+/// - `BFloat16` -> `Float32` (lossless widening)
+/// - 8-bit floats and `Float4E2M1` -> `Float16` (lossless), except
+///   `Float8E8M0` -> `Float32` since its range exceeds `Float16`
+/// - `Int2` -> `Int4` and `Uint2` -> `Uint4` (lossless repacking)
+pub fn webnn_lowering(dtype: &DataType) -> DataType {
+    match dtype {
+        DataType::Int2 => DataType::Int4,
+        DataType::Uint2 => DataType::Uint4,
+        DataType::Bool => DataType::Uint8,
+        DataType::Int16 => DataType::Int32,
+        DataType::Uint16 => DataType::Uint32,
+        DataType::Float64 | DataType::BFloat16 | DataType::Float8E8M0 => DataType::Float32,
+        DataType::Float8E4M3FN
+        | DataType::Float8E4M3FNUZ
+        | DataType::Float8E5M2
+        | DataType::Float8E5M2FNUZ
+        | DataType::Float4E2M1 => DataType::Float16,
+        other => other.clone(),
+    }
+}
+
+/// Describes which element types a WebNN backend can represent and how the
+/// remaining types are lowered.
+///
+/// Each unsupported type is rewritten to its fallback (see
+/// [`TypeLoweringPolicy::fallback`]) until a supported type is reached.
+/// Narrowing steps such as `Int64` -> `Int32` are range checked by
+/// [`TensorData::lower`](crate::tensor_data::TensorData::lower).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeLoweringPolicy {
+    supported: HashSet<DataType>,
+}
+
+impl Default for TypeLoweringPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeLoweringPolicy {
+    /// Policy for a backend supporting every native WebNN type.
+    pub fn new() -> Self {
+        Self::with_supported(&[
+            DataType::Float32,
+            DataType::Float16,
+            DataType::Int32,
+            DataType::Uint32,
+            DataType::Int64,
+            DataType::Uint64,
+            DataType::Int8,
+            DataType::Uint8,
+            DataType::Int4,
+            DataType::Uint4,
+        ])
+    }
+
+    pub fn with_supported(types: &[DataType]) -> Self {
+        Self {
+            supported: types.iter().cloned().collect(),
+        }
+    }
+
+    pub fn with(mut self, dtype: DataType) -> Self {
+        self.supported.insert(dtype);
+        self
+    }
+
+    pub fn without(mut self, dtype: DataType) -> Self {
+        self.supported.remove(&dtype);
+        self
+    }
+
+    pub fn is_supported(&self, dtype: &DataType) -> bool {
+        self.supported.contains(dtype)
+    }
+
+    /// The next type tried when `dtype` is not supported, if any.
+    pub fn fallback(dtype: &DataType) -> Option<DataType> {
+        match dtype {
+            DataType::Int64 | DataType::Int16 | DataType::Int8 | DataType::Uint8 => {
+                Some(DataType::Int32)
+            }
+            DataType::Uint64 | DataType::Uint16 => Some(DataType::Uint32),
+            DataType::Float64 | DataType::Float16 | DataType::BFloat16 | DataType::Float8E8M0 => {
+                Some(DataType::Float32)
+            }
+            DataType::Float8E4M3FN
+            | DataType::Float8E4M3FNUZ
+            | DataType::Float8E5M2
+            | DataType::Float8E5M2FNUZ
+            | DataType::Float4E2M1 => Some(DataType::Float16),
+            DataType::Bool => Some(DataType::Uint8),
+            DataType::Int2 => Some(DataType::Int4),
+            DataType::Uint2 => Some(DataType::Uint4),
+            DataType::Int4 => Some(DataType::Int8),
+            DataType::Uint4 => Some(DataType::Uint8),
+            DataType::Float32 | DataType::Int32 | DataType::Uint32 => None,
+        }
+    }
+
+    /// Resolves the type a tensor of `dtype` should be emitted as.
+    pub fn lower(&self, dtype: &DataType) -> Result<DataType> {
+        let mut current = dtype.clone();
+        while !self.is_supported(&current) {
+            current = Self::fallback(&current).ok_or_else(|| {
+                ConversionError::UnsupportedDataType(format!(
+                    "{dtype:?} has no supported lowering (stopped at {current:?})"
+                ))
+            })?;
+        }
+        Ok(current)
+    }
+
+    /// Returns true if lowering `dtype` can lose values and therefore needs a
+    /// range check on the tensor contents.
+    pub fn is_narrowing(&self, dtype: &DataType) -> Result<bool> {
+        let lowered = self.lower(dtype)?;
+        Ok(matches!(
+            (dtype, &lowered),
+            (DataType::Int64, DataType::Int32)
+                | (DataType::Uint64, DataType::Uint32)
+                | (DataType::Float64, DataType::Float32)
+        ))
+    }
+}
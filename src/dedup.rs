@@ -0,0 +1,130 @@
+//! Content hashing of tensors and deduplication of identical initializers.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::data_types::{DataType, onnx_to_webnn};
+use crate::error::Result;
+use crate::external_data::ExternalDataInfo;
+use crate::protos::onnx::TensorProto;
+use crate::tensor::Tensor;
+use crate::tensor_data::{TensorData, TensorView, element_bytes, element_count_of};
+
+/// A 64-bit FNV-1a hash of a tensor's data type, shape and elements.
+///
+/// The hash only depends on the values, so it is the same on every host and
+/// for every encoding of the same tensor (`raw_data` or the typed fields,
+/// any padding bits of packed sub-byte types). It is not cryptographic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ContentHash(pub u64);
+
+impl fmt::Display for ContentHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl TensorData {
+    /// Hashes the payload as a tensor of `dtype` and `shape`.
+    pub fn content_hash(&self, dtype: &DataType, shape: &[i64]) -> Result<ContentHash> {
+        self.check_shape("", dtype, shape)?;
+        let elements = element_bytes(self, dtype, element_count_of(shape)?)?;
+        Ok(hash_parts(dtype, shape, &elements))
+    }
+}
+
+impl Tensor {
+    pub fn content_hash(&self) -> ContentHash {
+        let elements = element_bytes(self.data(), self.dtype(), self.element_count())
+            .expect("payload was validated in Tensor::new");
+        hash_parts(self.dtype(), self.shape(), &elements)
+    }
+}
+
+/// Finds initializers with the same data type, shape and values as an
+/// earlier one. Returns a map from each duplicate's name to the name of the
+/// first initializer with that content, so converters can emit it once.
+///
+/// Hash matches are confirmed by comparing the values. External initializers
+/// are never aliased since their bytes are not loaded, and neither are
+/// element types without a WebNN counterpart such as strings. `raw_data` is
+/// hashed in place; only one initializer is decoded at a time.
+pub fn dedup_initializers(initializers: &[TensorProto]) -> Result<HashMap<String, String>> {
+    let mut aliases = HashMap::new();
+    // indices of canonical initializers by hash; several on a collision
+    let mut seen: HashMap<ContentHash, Vec<usize>> = HashMap::new();
+    for (index, init) in initializers.iter().enumerate() {
+        if ExternalDataInfo::from_tensor_proto(init)?.is_some() {
+            continue;
+        }
+        let Ok(dtype) = onnx_to_webnn(init.data_type) else {
+            continue;
+        };
+        let elements = proto_element_bytes(init, &dtype)?;
+        let candidates = seen
+            .entry(hash_parts(&dtype, &init.dims, &elements))
+            .or_default();
+        let mut canonical = None;
+        for &other in candidates.iter() {
+            let other = &initializers[other];
+            if other.data_type == init.data_type
+                && other.dims == init.dims
+                && proto_element_bytes(other, &dtype)? == elements
+            {
+                canonical = Some(other);
+                break;
+            }
+        }
+        match canonical {
+            Some(canonical) => {
+                aliases.insert(init.name.clone(), canonical.name.clone());
+            }
+            None => candidates.push(index),
+        }
+    }
+    Ok(aliases)
+}
+
+/// The hashed element bytes of `init`, borrowed from `raw_data` where that
+/// already is the canonical encoding.
+fn proto_element_bytes<'a>(init: &'a TensorProto, dtype: &DataType) -> Result<Cow<'a, [u8]>> {
+    // sub-byte codes are unpacked and bools normalized to 0 or 1
+    if !init.raw_data.is_empty() && dtype.bit_width() >= 8 && *dtype != DataType::Bool {
+        return Ok(Cow::Borrowed(TensorView::from_tensor_proto(init)?.bytes));
+    }
+    let data = TensorData::from_tensor_proto(init)?;
+    Ok(Cow::Owned(element_bytes(
+        &data,
+        dtype,
+        element_count_of(&init.dims)?,
+    )?))
+}
+
+fn hash_parts(dtype: &DataType, shape: &[i64], elements: &[u8]) -> ContentHash {
+    let mut hasher = Fnv1a::default();
+    hasher.write(dtype.name().as_bytes());
+    hasher.write(&[0]);
+    hasher.write(&(shape.len() as u64).to_le_bytes());
+    for dim in shape {
+        hasher.write(&dim.to_le_bytes());
+    }
+    hasher.write(elements);
+    ContentHash(hasher.0)
+}
+
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
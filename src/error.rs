@@ -10,9 +10,21 @@ pub enum ConversionError {
     #[error("invalid attribute: {0}")]
     InvalidAttribute(String),
 
+    #[error("unsupported data type: {0}")]
+    UnsupportedDataType(String),
+
+    #[error("unsupported value type: {0}")]
+    UnsupportedValueType(String),
+
     #[error("invalid tensor data: {0}")]
     InvalidTensorData(String),
 
+    #[error("value out of range: {0}")]
+    ValueOutOfRange(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
     #[error("internal error: {0}")]
     Internal(String),
 }
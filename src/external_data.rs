@@ -0,0 +1,261 @@
+//! ONNX external data: tensors whose bytes live in a side file next to the
+//! model (`TensorProto.data_location = EXTERNAL`).
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+
+use crate::data_types::onnx_to_webnn;
+use crate::error::{ConversionError, Result};
+use crate::protos::onnx::tensor_proto::DataLocation;
+use crate::protos::onnx::{StringStringEntryProto, TensorProto};
+use crate::tensor_data::{CountValidation, TensorData};
+
+/// Offsets of spilled tensors are aligned to this, as the ONNX spec
+/// recommends for mmap support.
+pub const EXTERNAL_DATA_ALIGNMENT: u64 = 4096;
+
+/// Tensors with at least this many bytes are spilled by default.
+pub const DEFAULT_SIZE_THRESHOLD: usize = 1024;
+
+/// The parsed `TensorProto.external_data` entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalDataInfo {
+    pub location: String,
+    pub offset: u64,
+    pub length: Option<u64>,
+    /// SHA1 of the whole file; carried through but not verified.
+    pub checksum: Option<String>,
+}
+
+impl ExternalDataInfo {
+    /// Returns `None` for tensors stored inside the protobuf.
+    pub fn from_tensor_proto(tensor: &TensorProto) -> Result<Option<Self>> {
+        if tensor.data_location != DataLocation::External as i32 {
+            return Ok(None);
+        }
+
+        let mut location = None;
+        let mut offset = 0;
+        let mut length = None;
+        let mut checksum = None;
+        for entry in &tensor.external_data {
+            match entry.key.as_str() {
+                "location" => location = Some(entry.value.clone()),
+                "offset" => offset = parse_u64(tensor, "offset", &entry.value)?,
+                "length" => length = Some(parse_u64(tensor, "length", &entry.value)?),
+                "checksum" => checksum = Some(entry.value.clone()),
+                _ => {}
+            }
+        }
+
+        let location = location.ok_or_else(|| {
+            ConversionError::InvalidTensorData(format!(
+                "tensor '{}' is external but has no location",
+                tensor.name
+            ))
+        })?;
+        Ok(Some(Self {
+            location,
+            offset,
+            length,
+            checksum,
+        }))
+    }
+
+    pub fn to_entries(&self) -> Vec<StringStringEntryProto> {
+        let mut entries = vec![entry("location", &self.location)];
+        if self.offset != 0 {
+            entries.push(entry("offset", &self.offset.to_string()));
+        }
+        if let Some(length) = self.length {
+            entries.push(entry("length", &length.to_string()));
+        }
+        if let Some(checksum) = &self.checksum {
+            entries.push(entry("checksum", checksum));
+        }
+        entries
+    }
+}
+
+/// Reads external tensor data relative to the directory of the model file.
+#[derive(Debug, Clone)]
+pub struct ExternalDataResolver {
+    base_dir: PathBuf,
+}
+
+impl ExternalDataResolver {
+    pub fn new(base_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            base_dir: base_dir.into(),
+        }
+    }
+
+    /// Resolver for the model at `model_path`.
+    pub fn for_model(model_path: &Path) -> Self {
+        Self::new(model_path.parent().unwrap_or(Path::new("")))
+    }
+
+    /// Reads the byte range described by `info`. Without a `length`, reads to
+    /// the end of the file.
+    pub fn read_bytes(&self, info: &ExternalDataInfo) -> Result<Vec<u8>> {
+        let path = resolve_location(&self.base_dir, &info.location)?;
+        let mut file = File::open(&path)?;
+        let file_len = file.metadata()?.len();
+        let length = info
+            .length
+            .unwrap_or_else(|| file_len.saturating_sub(info.offset));
+        if info
+            .offset
+            .checked_add(length)
+            .is_none_or(|end| end > file_len)
+        {
+            return Err(ConversionError::InvalidTensorData(format!(
+                "external data range {}..+{length} exceeds {} ({file_len} bytes)",
+                info.offset,
+                path.display()
+            )));
+        }
+
+        file.seek(SeekFrom::Start(info.offset))?;
+        let mut bytes = vec![0; length as usize];
+        file.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Reads `tensor`, loading its bytes from disk if it is external.
+    pub fn load(&self, tensor: &TensorProto) -> Result<TensorData> {
+        let Some(info) = ExternalDataInfo::from_tensor_proto(tensor)? else {
+            return TensorData::from_tensor_proto(tensor);
+        };
+        let inlined = TensorProto {
+            raw_data: self.read_bytes(&info)?,
+            data_location: DataLocation::Default as i32,
+            external_data: vec![],
+            ..tensor.clone()
+        };
+        TensorData::from_tensor_proto_with(&inlined, CountValidation::Strict)
+    }
+
+    /// Moves the external bytes of `tensor` into `raw_data`.
+    pub fn inline(&self, tensor: &mut TensorProto) -> Result<()> {
+        if let Some(info) = ExternalDataInfo::from_tensor_proto(tensor)? {
+            tensor.raw_data = self.read_bytes(&info)?;
+            tensor.external_data.clear();
+            tensor.data_location = DataLocation::Default as i32;
+        }
+        Ok(())
+    }
+}
+
+/// Spills large tensors into a single external data file.
+pub struct ExternalDataWriter {
+    location: String,
+    file: File,
+    offset: u64,
+    size_threshold: usize,
+}
+
+impl ExternalDataWriter {
+    /// Creates (or truncates) `base_dir/location`.
+    pub fn create(base_dir: &Path, location: &str) -> Result<Self> {
+        let path = resolve_location(base_dir, location)?;
+        Ok(Self {
+            location: location.to_string(),
+            file: File::create(path)?,
+            offset: 0,
+            size_threshold: DEFAULT_SIZE_THRESHOLD,
+        })
+    }
+
+    pub fn with_size_threshold(mut self, size_threshold: usize) -> Self {
+        self.size_threshold = size_threshold;
+        self
+    }
+
+    /// Moves the `raw_data` of `tensor` to the external file if it has at least
+    /// `size_threshold` bytes. Returns whether the tensor was spilled.
+    ///
+    /// Tensors using the typed fields are converted to `raw_data` first.
+    /// Tensors without any data, and string tensors, which have no raw
+    /// encoding, stay inline.
+    pub fn spill(&mut self, tensor: &mut TensorProto) -> Result<bool> {
+        if tensor.data_location == DataLocation::External as i32
+            || onnx_to_webnn(tensor.data_type).is_err()
+        {
+            return Ok(false);
+        }
+        if tensor.raw_data.is_empty() {
+            let data = TensorData::from_tensor_proto_with(tensor, CountValidation::AllowEmpty)?;
+            if data.byte_len() == 0 || data.byte_len() < self.size_threshold {
+                return Ok(false);
+            }
+            *tensor = TensorProto {
+                raw_data: data.into_bytes(),
+                float_data: vec![],
+                int32_data: vec![],
+                int64_data: vec![],
+                double_data: vec![],
+                uint64_data: vec![],
+                ..std::mem::take(tensor)
+            };
+        }
+        if tensor.raw_data.len() < self.size_threshold {
+            return Ok(false);
+        }
+
+        let padding = self.offset.next_multiple_of(EXTERNAL_DATA_ALIGNMENT) - self.offset;
+        self.file.write_all(&vec![0; padding as usize])?;
+        self.offset += padding;
+
+        let bytes = std::mem::take(&mut tensor.raw_data);
+        self.file.write_all(&bytes)?;
+        let info = ExternalDataInfo {
+            location: self.location.clone(),
+            offset: self.offset,
+            length: Some(bytes.len() as u64),
+            checksum: None,
+        };
+        self.offset += bytes.len() as u64;
+
+        tensor.external_data = info.to_entries();
+        tensor.data_location = DataLocation::External as i32;
+        Ok(true)
+    }
+
+    pub fn finish(mut self) -> Result<()> {
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+/// Joins `location` onto `base_dir`, rejecting absolute paths and `..` so a
+/// model cannot point outside its directory.
+fn resolve_location(base_dir: &Path, location: &str) -> Result<PathBuf> {
+    let relative = Path::new(location);
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(ConversionError::InvalidTensorData(format!(
+            "external data location '{location}' must be a relative path inside the model directory"
+        )));
+    }
+    Ok(base_dir.join(relative))
+}
+
+fn parse_u64(tensor: &TensorProto, key: &str, value: &str) -> Result<u64> {
+    value.parse().map_err(|_| {
+        ConversionError::InvalidTensorData(format!(
+            "tensor '{}' has invalid external data {key} '{value}'",
+            tensor.name
+        ))
+    })
+}
+
+fn entry(key: &str, value: &str) -> StringStringEntryProto {
+    StringStringEntryProto {
+        key: key.to_string(),
+        value: value.to_string(),
+    }
+}
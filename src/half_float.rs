@@ -0,0 +1,180 @@
+//! Helpers for `Float16` and `BFloat16` payloads, which [`TensorData`] keeps
+//! as raw `u16` bits, and statistics over floating point tensors.
+
+use std::cmp::Ordering;
+
+use half::slice::{HalfBitsSliceExt, HalfFloatSliceExt};
+use half::{bf16, f16};
+
+use crate::data_types::DataType;
+use crate::error::{ConversionError, Result};
+use crate::tensor_data::TensorData;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HalfFormat {
+    /// IEEE 754 binary16: 5 exponent bits, 10 mantissa bits.
+    F16,
+    /// The upper half of a binary32: 8 exponent bits, 7 mantissa bits.
+    BF16,
+}
+
+impl HalfFormat {
+    pub fn from_data_type(dtype: &DataType) -> Option<Self> {
+        match dtype {
+            DataType::Float16 => Some(Self::F16),
+            DataType::BFloat16 => Some(Self::BF16),
+            _ => None,
+        }
+    }
+
+    /// Bit pattern of positive infinity; anything above it (ignoring the
+    /// sign) is NaN.
+    fn infinity_bits(self) -> u16 {
+        match self {
+            Self::F16 => 0x7c00,
+            Self::BF16 => 0x7f80,
+        }
+    }
+
+    pub fn to_f32(self, bits: u16) -> f32 {
+        match self {
+            Self::F16 => f16::from_bits(bits).to_f32(),
+            Self::BF16 => bf16::from_bits(bits).to_f32(),
+        }
+    }
+
+    /// Rounds to nearest-even; out-of-range values become infinity.
+    pub fn from_f32(self, value: f32) -> u16 {
+        match self {
+            Self::F16 => f16::from_f32(value).to_bits(),
+            Self::BF16 => bf16::from_f32(value).to_bits(),
+        }
+    }
+
+    /// Converts a whole slice, using the F16C/FP16 instructions when the CPU
+    /// has them.
+    pub fn to_f32_vec(self, bits: &[u16]) -> Vec<f32> {
+        match self {
+            Self::F16 => bits.reinterpret_cast::<f16>().to_f32_vec(),
+            Self::BF16 => bits.reinterpret_cast::<bf16>().to_f32_vec(),
+        }
+    }
+
+    /// Converts a whole slice; see [`HalfFormat::to_f32_vec`].
+    pub fn from_f32_slice(self, values: &[f32]) -> Vec<u16> {
+        let mut bits = vec![0u16; values.len()];
+        match self {
+            Self::F16 => bits
+                .reinterpret_cast_mut::<f16>()
+                .convert_from_f32_slice(values),
+            Self::BF16 => bits
+                .reinterpret_cast_mut::<bf16>()
+                .convert_from_f32_slice(values),
+        }
+        bits
+    }
+
+    pub fn is_nan(self, bits: u16) -> bool {
+        bits & 0x7fff > self.infinity_bits()
+    }
+
+    pub fn is_infinite(self, bits: u16) -> bool {
+        bits & 0x7fff == self.infinity_bits()
+    }
+
+    /// IEEE equality: `+0 == -0` and NaN equals nothing, not even itself.
+    pub fn ieee_eq(self, a: u16, b: u16) -> bool {
+        !self.is_nan(a) && !self.is_nan(b) && (a == b || (a | b) & 0x7fff == 0)
+    }
+
+    /// Total order in the manner of [`f32::total_cmp`]: `-NaN < -inf < ... <
+    /// -0 < +0 < ... < +inf < +NaN`.
+    pub fn total_cmp(self, a: u16, b: u16) -> Ordering {
+        ordered_key(a).cmp(&ordered_key(b))
+    }
+
+    /// Number of representable values between `a` and `b`, counting the two
+    /// zeros as one. `None` if either is NaN.
+    pub fn ulp_distance(self, a: u16, b: u16) -> Option<u32> {
+        if self.is_nan(a) || self.is_nan(b) {
+            return None;
+        }
+        // sign-magnitude to a signed line where both zeros map to 0
+        let position = |bits: u16| {
+            let magnitude = (bits & 0x7fff) as i32;
+            if bits & 0x8000 != 0 {
+                -magnitude
+            } else {
+                magnitude
+            }
+        };
+        Some(position(a).abs_diff(position(b)))
+    }
+}
+
+/// Maps sign-magnitude bits to an unsigned key with the same order.
+fn ordered_key(bits: u16) -> u16 {
+    if bits & 0x8000 != 0 {
+        !bits
+    } else {
+        bits | 0x8000
+    }
+}
+
+/// Summary of a floating point payload, see [`TensorData::float_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FloatStats {
+    pub count: usize,
+    pub nan_count: usize,
+    /// Positive and negative infinities.
+    pub inf_count: usize,
+    /// Smallest finite value, `None` if there is none.
+    pub min: Option<f64>,
+    /// Largest finite value, `None` if there is none.
+    pub max: Option<f64>,
+    /// Mean of the finite values, `None` if there are none.
+    pub mean: Option<f64>,
+}
+
+impl TensorData {
+    /// `Float16` payload rounded from `values`.
+    pub fn float16_from_f32(values: &[f32]) -> Self {
+        TensorData::Float16(HalfFormat::F16.from_f32_slice(values))
+    }
+
+    /// `BFloat16` payload rounded from `values`.
+    pub fn bfloat16_from_f32(values: &[f32]) -> Self {
+        TensorData::BFloat16(HalfFormat::BF16.from_f32_slice(values))
+    }
+
+    /// Counts NaNs and infinities and summarizes the finite elements. Every
+    /// float type is widened exactly to `f64` first, so 16-bit and 8-bit
+    /// payloads are never read as integers.
+    pub fn float_stats(&self) -> Result<FloatStats> {
+        if !self.data_type().is_some_and(|dt| dt.is_floating()) {
+            return Err(ConversionError::UnsupportedDataType(format!(
+                "float_stats expects floating point data, got {:?}",
+                self.data_type()
+            )));
+        }
+
+        let mut stats = FloatStats::default();
+        let mut finite = 0usize;
+        let mut sum = 0.0;
+        for x in self.iter_f64(None)? {
+            stats.count += 1;
+            if x.is_nan() {
+                stats.nan_count += 1;
+            } else if x.is_infinite() {
+                stats.inf_count += 1;
+            } else {
+                finite += 1;
+                sum += x;
+                stats.min = Some(stats.min.map_or(x, |m| m.min(x)));
+                stats.max = Some(stats.max.map_or(x, |m| m.max(x)));
+            }
+        }
+        stats.mean = (finite > 0).then(|| sum / finite as f64);
+        Ok(stats)
+    }
+}
@@ -0,0 +1,187 @@
+//! Physical transposition of tensors, and the named layouts WebNN `conv2d`
+//! accepts for its input (`inputLayout`) and filter (`filterLayout`).
+//!
+//! ONNX convolutions are always NCHW with OIHW filters of shape
+//! `[O, I / groups, kH, kW]`. The WebNN filter layouts are permutations of
+//! that same shape, so grouped and depthwise filters convert like any other:
+//! a depthwise OIHW filter `[C * M, 1, kH, kW]` becomes the `[1, kH, kW, C * M]`
+//! IHWO filter that NHWC backends expect.
+
+use crate::error::{ConversionError, Result};
+use crate::tensor::Tensor;
+
+impl Tensor {
+    /// Reorders the dimensions so that output dimension `i` is input
+    /// dimension `perm[i]`, moving the elements to match.
+    pub fn transpose(&self, perm: &[usize]) -> Result<Tensor> {
+        let mut seen = vec![false; self.rank()];
+        let valid = perm.len() == self.rank()
+            && perm
+                .iter()
+                .all(|&p| p < seen.len() && !std::mem::replace(&mut seen[p], true));
+        if !valid {
+            return Err(ConversionError::InvalidAttribute(format!(
+                "{perm:?} is not a permutation of the {} dimensions of {:?}",
+                self.rank(),
+                self.shape()
+            )));
+        }
+
+        let strides = self.strides();
+        let mut flat = vec![0usize];
+        for &p in perm {
+            let (dim, stride) = (self.shape()[p] as usize, strides[p]);
+            flat = flat
+                .iter()
+                .flat_map(|&base| (0..dim).map(move |c| base + c * stride))
+                .collect();
+        }
+        let shape = perm.iter().map(|&p| self.shape()[p]).collect();
+        self.take(&flat, shape)
+    }
+
+    /// Converts a 4-D activation between `from` and `to`.
+    pub fn convert_input_layout(&self, from: InputLayout, to: InputLayout) -> Result<Tensor> {
+        self.check_rank(4, "input")?;
+        self.transpose(&layout_perm(&from.axes(), &to.axes()))
+    }
+
+    /// Converts a convolution filter between `from` and `to`, checking that
+    /// it is a valid filter for a convolution with `groups` groups: the
+    /// output channels must split evenly into the groups.
+    pub fn convert_filter_layout(
+        &self,
+        from: FilterLayout,
+        to: FilterLayout,
+        groups: usize,
+    ) -> Result<Tensor> {
+        self.check_rank(4, "filter")?;
+        let dims = from.filter_dims(self.shape());
+        if groups == 0 || !dims.output_channels.is_multiple_of(groups) {
+            return Err(ConversionError::InvalidTensorData(format!(
+                "{} filter {:?} has {} output channels, which do not split into {groups} groups",
+                from.name(),
+                self.shape(),
+                dims.output_channels
+            )));
+        }
+        self.transpose(&layout_perm(&from.axes(), &to.axes()))
+    }
+
+    fn check_rank(&self, rank: usize, what: &str) -> Result<()> {
+        if self.rank() != rank {
+            return Err(ConversionError::InvalidTensorData(format!(
+                "{what} must have rank {rank}, got shape {:?}",
+                self.shape()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// WebNN `MLInputOperandLayout`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputLayout {
+    /// The ONNX layout.
+    #[default]
+    Nchw,
+    Nhwc,
+}
+
+impl InputLayout {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "nchw" => Some(Self::Nchw),
+            "nhwc" => Some(Self::Nhwc),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Nchw => "nchw",
+            Self::Nhwc => "nhwc",
+        }
+    }
+
+    /// Position of the N, C, H and W dimensions.
+    fn axes(self) -> [usize; 4] {
+        match self {
+            Self::Nchw => [0, 1, 2, 3],
+            Self::Nhwc => [0, 3, 1, 2],
+        }
+    }
+}
+
+/// WebNN `MLConv2dFilterOperandLayout`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterLayout {
+    /// The ONNX layout.
+    #[default]
+    Oihw,
+    Hwio,
+    Ohwi,
+    Ihwo,
+}
+
+/// Dimensions of a convolution filter, independent of its layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FilterDims {
+    pub output_channels: usize,
+    /// Input channels seen by each group, `I / groups`.
+    pub input_channels_per_group: usize,
+    pub height: usize,
+    pub width: usize,
+}
+
+impl FilterLayout {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "oihw" => Some(Self::Oihw),
+            "hwio" => Some(Self::Hwio),
+            "ohwi" => Some(Self::Ohwi),
+            "ihwo" => Some(Self::Ihwo),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Oihw => "oihw",
+            Self::Hwio => "hwio",
+            Self::Ohwi => "ohwi",
+            Self::Ihwo => "ihwo",
+        }
+    }
+
+    /// Reads the dimensions of a 4-D filter `shape` in this layout.
+    pub fn filter_dims(self, shape: &[i64]) -> FilterDims {
+        let [o, i, h, w] = self.axes().map(|axis| shape[axis] as usize);
+        FilterDims {
+            output_channels: o,
+            input_channels_per_group: i,
+            height: h,
+            width: w,
+        }
+    }
+
+    /// Position of the O, I, H and W dimensions.
+    fn axes(self) -> [usize; 4] {
+        match self {
+            Self::Oihw => [0, 1, 2, 3],
+            Self::Hwio => [3, 2, 0, 1],
+            Self::Ohwi => [0, 3, 1, 2],
+            Self::Ihwo => [3, 0, 1, 2],
+        }
+    }
+}
+
+/// Permutation taking a tensor whose logical dimensions sit at `from` to one
+/// where they sit at `to`.
+fn layout_perm(from: &[usize; 4], to: &[usize; 4]) -> Vec<usize> {
+    let mut perm = vec![0; 4];
+    for (&f, &t) in from.iter().zip(to) {
+        perm[t] = f;
+    }
+    perm
+}
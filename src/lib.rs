@@ -3,11 +3,25 @@
 pub mod protos;
 
 pub mod attributes;
+pub mod cast;
+pub mod compare;
+pub mod constant_folding;
 pub mod data_types;
+pub mod dedup;
 pub mod error;
+pub mod external_data;
+pub mod half_float;
 pub mod identifiers;
+pub mod layout;
+pub mod minifloat;
+pub mod npy;
+pub mod operand_descriptor;
 pub mod operation_names;
+pub mod quantization;
+pub mod safetensors;
 pub mod shape_inference;
+pub mod streaming;
+pub mod tensor;
 pub mod tensor_data;
 
 pub use error::{ConversionError, Result};
@@ -0,0 +1,247 @@
+//! Bit-level codecs for the ONNX 8-bit and 4-bit floating point formats.
+//!
+//! Conversions follow the ONNX `Cast` specification: values are rounded to
+//! nearest-even, and `saturate` decides whether out-of-range inputs clamp to
+//! the largest finite value or become NaN (infinity for `E5M2`).
+
+use crate::data_types::DataType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MiniFloatFormat {
+    /// 4 exponent bits, 3 mantissa bits, no infinities, NaN is `S.1111.111`.
+    E4M3FN,
+    /// Like `E4M3FN` with bias 8, no negative zero and a single NaN `0x80`.
+    E4M3FNUZ,
+    /// IEEE 754 style: 5 exponent bits, 2 mantissa bits, infinities and NaNs.
+    E5M2,
+    /// Like `E5M2` with bias 16, no infinities, no negative zero and NaN `0x80`.
+    E5M2FNUZ,
+    /// Unsigned power of two `2^(e - 127)`, `0xFF` is NaN.
+    E8M0,
+    /// 1 sign, 2 exponent and 1 mantissa bit; no infinities or NaNs.
+    E2M1,
+}
+
+impl MiniFloatFormat {
+    pub fn from_data_type(dtype: &DataType) -> Option<Self> {
+        match dtype {
+            DataType::Float8E4M3FN => Some(Self::E4M3FN),
+            DataType::Float8E4M3FNUZ => Some(Self::E4M3FNUZ),
+            DataType::Float8E5M2 => Some(Self::E5M2),
+            DataType::Float8E5M2FNUZ => Some(Self::E5M2FNUZ),
+            DataType::Float8E8M0 => Some(Self::E8M0),
+            DataType::Float4E2M1 => Some(Self::E2M1),
+            _ => None,
+        }
+    }
+
+    /// Exponent bits, mantissa bits and exponent bias.
+    fn layout(self) -> (u32, u32, i32) {
+        match self {
+            Self::E4M3FN => (4, 3, 7),
+            Self::E4M3FNUZ => (4, 3, 8),
+            Self::E5M2 => (5, 2, 15),
+            Self::E5M2FNUZ => (5, 2, 16),
+            Self::E8M0 => (8, 0, 127),
+            Self::E2M1 => (2, 1, 1),
+        }
+    }
+
+    fn sign_bit(self) -> u8 {
+        let (e, m, _) = self.layout();
+        1 << (e + m)
+    }
+
+    fn is_uz(self) -> bool {
+        matches!(self, Self::E4M3FNUZ | Self::E5M2FNUZ)
+    }
+
+    /// Bit pattern of the largest finite positive value.
+    pub fn max_code(self) -> u8 {
+        match self {
+            Self::E4M3FN => 0x7E,
+            Self::E4M3FNUZ | Self::E5M2FNUZ => 0x7F,
+            Self::E5M2 => 0x7B,
+            Self::E8M0 => 0xFE,
+            Self::E2M1 => 0x07,
+        }
+    }
+
+    /// Canonical NaN bit pattern, if the format has one.
+    pub fn nan_code(self) -> Option<u8> {
+        match self {
+            Self::E4M3FN | Self::E5M2 => Some(0x7F),
+            Self::E4M3FNUZ | Self::E5M2FNUZ => Some(0x80),
+            Self::E8M0 => Some(0xFF),
+            Self::E2M1 => None,
+        }
+    }
+
+    /// Largest finite value of the format.
+    pub fn max_value(self) -> f32 {
+        decode(self, self.max_code())
+    }
+}
+
+/// Decodes one element. Every value of these formats is exact in `f32`.
+pub fn decode(format: MiniFloatFormat, bits: u8) -> f32 {
+    if format == MiniFloatFormat::E8M0 {
+        return match bits {
+            0xFF => f32::NAN,
+            // 2^-127 is an f32 subnormal
+            0 => f32::from_bits(0x0040_0000),
+            e => f32::from_bits((e as u32) << 23),
+        };
+    }
+
+    let (e_bits, m_bits, bias) = format.layout();
+    let negative = bits & format.sign_bit() != 0;
+    let exp = ((bits >> m_bits) & ((1 << e_bits) - 1)) as i32;
+    let man = (bits & ((1 << m_bits) - 1)) as i32;
+    let exp_max = (1 << e_bits) - 1;
+    let man_max = (1 << m_bits) - 1;
+
+    match format {
+        MiniFloatFormat::E4M3FNUZ | MiniFloatFormat::E5M2FNUZ if bits == 0x80 => {
+            return f32::NAN;
+        }
+        MiniFloatFormat::E4M3FN if exp == exp_max && man == man_max => return f32::NAN,
+        MiniFloatFormat::E5M2 if exp == exp_max => {
+            return match (man, negative) {
+                (0, false) => f32::INFINITY,
+                (0, true) => f32::NEG_INFINITY,
+                _ => f32::NAN,
+            };
+        }
+        _ => {}
+    }
+
+    let magnitude = if exp == 0 {
+        man as f32 * pow2(1 - bias - m_bits as i32)
+    } else {
+        ((1 << m_bits) | man) as f32 * pow2(exp - bias - m_bits as i32)
+    };
+    if negative { -magnitude } else { magnitude }
+}
+
+/// Encodes one element with round-to-nearest-even.
+///
+/// With `saturate`, infinities and values beyond the largest finite value clamp
+/// to it. Without it they become NaN, or infinity for `E5M2`. `E2M1` has
+/// neither, so it always saturates and maps NaN to its largest magnitude.
+/// `E8M0` is unsigned and rounds up to the next power of two, the ONNX default
+/// rounding mode for that type.
+pub fn encode(format: MiniFloatFormat, value: f32, saturate: bool) -> u8 {
+    encode_f64(format, value as f64, saturate)
+}
+
+/// [`encode`] for an `f64`, rounding once rather than through `f32`.
+pub fn encode_f64(format: MiniFloatFormat, value: f64, saturate: bool) -> u8 {
+    if format == MiniFloatFormat::E8M0 {
+        return match round_magnitude(format, value.abs()) {
+            _ if value.is_nan() => 0xFF,
+            Some(code) if value.is_finite() => code,
+            _ if saturate => 0xFE,
+            _ => 0xFF,
+        };
+    }
+
+    let sign = if value.is_sign_negative() {
+        format.sign_bit()
+    } else {
+        0
+    };
+    let saturate = saturate || format == MiniFloatFormat::E2M1;
+    let nan = || match format.nan_code() {
+        Some(0x80) => 0x80,
+        Some(code) => sign | code,
+        None => sign | format.max_code(),
+    };
+    let overflow = || {
+        if saturate {
+            sign | format.max_code()
+        } else if format == MiniFloatFormat::E5M2 {
+            sign | 0x7C
+        } else {
+            nan()
+        }
+    };
+
+    if value.is_nan() {
+        return nan();
+    }
+    if value.is_infinite() {
+        return overflow();
+    }
+    match round_magnitude(format, value.abs()) {
+        Some(0) if format.is_uz() => 0,
+        Some(code) => sign | code,
+        None => overflow(),
+    }
+}
+
+/// Whether the finite `value` rounds past the largest finite value of
+/// `format`, so that [`encode`] has to saturate or overflow.
+pub fn overflows(format: MiniFloatFormat, value: f64) -> bool {
+    value.is_finite() && round_magnitude(format, value.abs()).is_none()
+}
+
+/// Bit pattern of the finite magnitude `a` rounded to `format`, or `None`
+/// if it rounds past the largest finite value.
+fn round_magnitude(format: MiniFloatFormat, a: f64) -> Option<u8> {
+    let biased = (a.to_bits() >> 52) as i32;
+    if format == MiniFloatFormat::E8M0 {
+        if biased == 0 {
+            // zero and f64 subnormals round up to 2^-127
+            return Some(0);
+        }
+        // a lies in [2^e, 2^(e + 1)); anything above 2^e rounds up
+        let inexact = a.to_bits() & ((1 << 52) - 1) != 0;
+        let code = (biased - 1023 + 127 + inexact as i32).max(0);
+        return (code <= 0xFE).then_some(code as u8);
+    }
+
+    if a == 0.0 {
+        return Some(0);
+    }
+    let (e_bits, m_bits, bias) = format.layout();
+    // f64 subnormals fall far below emin and round to zero
+    let emin = 1 - bias;
+    let exp = (biased - 1023).max(emin);
+    let quantum = 2f64.powi(exp - m_bits as i32);
+    let n = round_half_even(a / quantum);
+    if n == 0.0 {
+        return Some(0);
+    }
+    if n * quantum > format.max_value() as f64 {
+        return None;
+    }
+
+    let mut n = n as u32;
+    let mut exp = exp;
+    if n >= 2 << m_bits {
+        n >>= 1;
+        exp += 1;
+    }
+    let (exp_field, man) = if n < 1 << m_bits {
+        (0, n)
+    } else {
+        ((exp + bias) as u32, n - (1 << m_bits))
+    };
+    debug_assert!(exp_field < 1 << e_bits);
+    Some(((exp_field << m_bits) | man) as u8)
+}
+
+fn pow2(exp: i32) -> f32 {
+    2f32.powi(exp)
+}
+
+fn round_half_even(x: f64) -> f64 {
+    let floor = x.floor();
+    let diff = x - floor;
+    if diff > 0.5 || (diff == 0.5 && floor % 2.0 != 0.0) {
+        floor + 1.0
+    } else {
+        floor
+    }
+}
@@ -0,0 +1,219 @@
+//! NumPy `.npy` and `.npz` files, for test fixtures and reference outputs.
+//!
+//! Arrays must be C-ordered. Reading accepts both byte orders; writing is
+//! always little-endian. Types NumPy has no `descr` for (bfloat16, the 8-bit
+//! and 4-bit floats, sub-byte integers) cannot be stored.
+
+use std::io::{Read, Seek, Write};
+
+use crate::data_types::DataType;
+use crate::error::{ConversionError, Result};
+use crate::tensor::Tensor;
+use crate::tensor_data::{TensorData, element_count_of};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Headers are padded so the data starts on this boundary, as NumPy does.
+const HEADER_ALIGNMENT: usize = 64;
+
+/// Reads one `.npy` array.
+pub fn read_npy(mut reader: impl Read) -> Result<Tensor> {
+    let mut preamble = [0u8; 8];
+    reader.read_exact(&mut preamble)?;
+    if &preamble[..6] != MAGIC {
+        return Err(invalid("missing NUMPY magic".to_string()));
+    }
+    let header_len = match preamble[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        major => return Err(invalid(format!("unsupported format version {major}"))),
+    };
+    let mut header = vec![0u8; header_len];
+    reader.read_exact(&mut header)?;
+    let header =
+        String::from_utf8(header).map_err(|_| invalid("header is not UTF-8".to_string()))?;
+    let (dtype, big_endian, shape) = parse_header(&header)?;
+
+    let mut bytes = vec![0u8; dtype.storage_len(element_count_of(&shape)?)];
+    reader.read_exact(&mut bytes)?;
+    if big_endian {
+        for element in bytes.chunks_exact_mut(dtype.byte_size()) {
+            element.reverse();
+        }
+    }
+    Tensor::new(dtype, shape, TensorData::Raw(bytes))
+}
+
+/// Writes `tensor` as a little-endian, C-ordered `.npy` array.
+pub fn write_npy(mut writer: impl Write, tensor: &Tensor) -> Result<()> {
+    let descr = descr(tensor.dtype())?;
+    let shape = match tensor.shape() {
+        [d] => format!("({d},)"),
+        dims => {
+            let dims: Vec<String> = dims.iter().map(|d| d.to_string()).collect();
+            format!("({})", dims.join(", "))
+        }
+    };
+    let mut header = format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': {shape}, }}");
+
+    // header length after padding and the trailing newline, for a prefix
+    // (magic, version and length field) of `prefix_len` bytes
+    let padded_len = |prefix_len: usize| {
+        (prefix_len + header.len() + 1).next_multiple_of(HEADER_ALIGNMENT) - prefix_len
+    };
+    // version 1 stores the header length in a u16
+    let (version, padded) = if padded_len(10) <= u16::MAX as usize {
+        (1u8, padded_len(10))
+    } else {
+        (2u8, padded_len(12))
+    };
+    header.extend(std::iter::repeat_n(' ', padded - header.len() - 1));
+    header.push('\n');
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[version, 0])?;
+    if version == 1 {
+        writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    } else {
+        writer.write_all(&(header.len() as u32).to_le_bytes())?;
+    }
+    writer.write_all(header.as_bytes())?;
+    writer.write_all(&tensor.data().as_bytes())?;
+    Ok(())
+}
+
+/// Reads every array of an `.npz` archive, in archive order, with the `.npy`
+/// suffix stripped from the names. Stored and deflated entries are supported.
+pub fn read_npz(reader: impl Read + Seek) -> Result<Vec<(String, Tensor)>> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(zip_error)?;
+    (0..archive.len())
+        .map(|i| {
+            let entry = archive.by_index(i).map_err(zip_error)?;
+            let name = entry.name();
+            let name = name.strip_suffix(".npy").unwrap_or(name).to_string();
+            Ok((name, read_npy(entry)?))
+        })
+        .collect()
+}
+
+/// Writes `arrays` to an uncompressed `.npz` archive, like `numpy.savez`.
+pub fn write_npz<W: Write + Seek>(writer: W, arrays: &[(&str, &Tensor)]) -> Result<W> {
+    let mut archive = zip::ZipWriter::new(writer);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Stored)
+        .large_file(true);
+    for (name, tensor) in arrays {
+        archive
+            .start_file(format!("{name}.npy"), options)
+            .map_err(zip_error)?;
+        write_npy(&mut archive, tensor)?;
+    }
+    archive.finish().map_err(zip_error)
+}
+
+/// NumPy type string for `dtype`, little-endian.
+fn descr(dtype: &DataType) -> Result<&'static str> {
+    Ok(match dtype {
+        DataType::Bool => "|b1",
+        DataType::Int8 => "|i1",
+        DataType::Uint8 => "|u1",
+        DataType::Int16 => "<i2",
+        DataType::Uint16 => "<u2",
+        DataType::Int32 => "<i4",
+        DataType::Uint32 => "<u4",
+        DataType::Int64 => "<i8",
+        DataType::Uint64 => "<u8",
+        DataType::Float16 => "<f2",
+        DataType::Float32 => "<f4",
+        DataType::Float64 => "<f8",
+        other => {
+            return Err(ConversionError::UnsupportedDataType(format!(
+                "{other} has no NumPy equivalent"
+            )));
+        }
+    })
+}
+
+/// Parses the Python dict literal of a `.npy` header into the data type,
+/// whether it is big-endian, and the shape.
+fn parse_header(header: &str) -> Result<(DataType, bool, Vec<i64>)> {
+    let descr = header_value(header, "descr")?;
+    let descr = descr
+        .strip_prefix(['\'', '"'])
+        .and_then(|d| d.split(['\'', '"']).next())
+        .ok_or_else(|| invalid(format!("invalid descr in {header}")))?;
+    let (order, code) = match descr.chars().next() {
+        Some(c @ ('<' | '>' | '|' | '=')) => (c, &descr[1..]),
+        _ => ('=', descr),
+    };
+    let dtype = match code {
+        "b1" | "?" => DataType::Bool,
+        "i1" => DataType::Int8,
+        "u1" => DataType::Uint8,
+        "i2" => DataType::Int16,
+        "u2" => DataType::Uint16,
+        "i4" => DataType::Int32,
+        "u4" => DataType::Uint32,
+        "i8" => DataType::Int64,
+        "u8" => DataType::Uint64,
+        "f2" => DataType::Float16,
+        "f4" => DataType::Float32,
+        "f8" => DataType::Float64,
+        _ => {
+            return Err(ConversionError::UnsupportedDataType(format!(
+                "NumPy dtype '{descr}'"
+            )));
+        }
+    };
+    let big_endian = order == '>' || (order == '=' && cfg!(target_endian = "big"));
+
+    if header_value(header, "fortran_order")?.starts_with("True") {
+        return Err(invalid(
+            "Fortran-ordered arrays are not supported".to_string(),
+        ));
+    }
+
+    let shape = header_value(header, "shape")?;
+    let shape = shape
+        .strip_prefix('(')
+        .and_then(|s| s.split(')').next())
+        .ok_or_else(|| invalid(format!("invalid shape in {header}")))?;
+    let shape = shape
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| d.trim_end_matches('L').parse::<i64>())
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|_| invalid(format!("invalid shape in {header}")))?;
+    Ok((dtype, big_endian, shape))
+}
+
+/// The text following `'key':` in the header.
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str> {
+    [format!("'{key}'"), format!("\"{key}\"")]
+        .iter()
+        .find_map(|quoted| {
+            let rest = &header[header.find(quoted.as_str())? + quoted.len()..];
+            Some(rest.trim_start().strip_prefix(':')?.trim_start())
+        })
+        .ok_or_else(|| invalid(format!("header has no '{key}': {header}")))
+}
+
+fn invalid(message: String) -> ConversionError {
+    ConversionError::InvalidTensorData(format!("npy: {message}"))
+}
+
+fn zip_error(error: zip::result::ZipError) -> ConversionError {
+    match error {
+        zip::result::ZipError::Io(e) => ConversionError::Io(e),
+        other => ConversionError::InvalidTensorData(format!("npz: {other}")),
+    }
+}
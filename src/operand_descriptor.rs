@@ -0,0 +1,122 @@
+use crate::data_types::{DataType, onnx_to_webnn, webnn_to_onnx};
+use crate::error::{ConversionError, Result};
+use crate::protos::onnx::tensor_shape_proto::{Dimension, dimension};
+use crate::protos::onnx::{TensorShapeProto, TypeProto, ValueInfoProto, type_proto};
+use crate::shape_inference::{Dim, TensorShape};
+
+/// Element type and shape of a WebNN operand, the counterpart of an ONNX
+/// tensor `TypeProto`.
+///
+/// ONNX dimensions without a `dim_value` or `dim_param` are represented as
+/// `Dim::Dynamic` with an empty name, and written back the same way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperandDescriptor {
+    pub data_type: DataType,
+    pub shape: TensorShape,
+}
+
+impl OperandDescriptor {
+    pub fn new(data_type: DataType, shape: TensorShape) -> Self {
+        Self { data_type, shape }
+    }
+
+    pub fn to_type_proto(&self) -> TypeProto {
+        let dim = self
+            .shape
+            .dims
+            .iter()
+            .map(|d| Dimension {
+                value: match d {
+                    Dim::Known(v) => Some(dimension::Value::DimValue(*v)),
+                    Dim::Dynamic(name) if name.is_empty() => None,
+                    Dim::Dynamic(name) => Some(dimension::Value::DimParam(name.clone())),
+                },
+                ..Default::default()
+            })
+            .collect();
+
+        TypeProto {
+            value: Some(type_proto::Value::TensorType(type_proto::Tensor {
+                elem_type: webnn_to_onnx(self.data_type.clone()) as i32,
+                shape: Some(TensorShapeProto { dim }),
+            })),
+            ..Default::default()
+        }
+    }
+
+    pub fn to_value_info(&self, name: &str) -> ValueInfoProto {
+        ValueInfoProto {
+            name: name.to_string(),
+            r#type: Some(self.to_type_proto()),
+            ..Default::default()
+        }
+    }
+
+    fn from_type_proto(ty: &TypeProto, name: &str) -> Result<Self> {
+        let tensor = match &ty.value {
+            Some(type_proto::Value::TensorType(t)) => t,
+            Some(type_proto::Value::SequenceType(_)) => {
+                return Err(unsupported(name, "sequence types have no WebNN equivalent"));
+            }
+            Some(type_proto::Value::MapType(_)) => {
+                return Err(unsupported(name, "map types have no WebNN equivalent"));
+            }
+            Some(type_proto::Value::OptionalType(_)) => {
+                return Err(unsupported(name, "optional types have no WebNN equivalent"));
+            }
+            Some(type_proto::Value::SparseTensorType(_)) => {
+                return Err(unsupported(name, "sparse tensors must be densified first"));
+            }
+            None => return Err(unsupported(name, "type is not set")),
+        };
+
+        let data_type = onnx_to_webnn(tensor.elem_type)?;
+        let shape = tensor
+            .shape
+            .as_ref()
+            .ok_or_else(|| unsupported(name, "shape (rank) is unknown"))?;
+        let dims = shape
+            .dim
+            .iter()
+            .map(|d| match &d.value {
+                Some(dimension::Value::DimValue(v)) => Dim::Known(*v),
+                Some(dimension::Value::DimParam(p)) => Dim::Dynamic(p.clone()),
+                None => Dim::Dynamic(String::new()),
+            })
+            .collect();
+
+        Ok(Self::new(data_type, TensorShape::new(dims)))
+    }
+}
+
+impl TryFrom<&TypeProto> for OperandDescriptor {
+    type Error = ConversionError;
+
+    fn try_from(ty: &TypeProto) -> Result<Self> {
+        Self::from_type_proto(ty, "<anonymous>")
+    }
+}
+
+impl TryFrom<&ValueInfoProto> for OperandDescriptor {
+    type Error = ConversionError;
+
+    fn try_from(info: &ValueInfoProto) -> Result<Self> {
+        let ty = info
+            .r#type
+            .as_ref()
+            .ok_or_else(|| unsupported(&info.name, "type is not set"))?;
+        Self::from_type_proto(ty, &info.name)
+    }
+}
+
+/// Produces a `ValueInfoProto` with an empty name; use
+/// [`OperandDescriptor::to_value_info`] to name it.
+impl From<OperandDescriptor> for ValueInfoProto {
+    fn from(desc: OperandDescriptor) -> Self {
+        desc.to_value_info("")
+    }
+}
+
+fn unsupported(name: &str, reason: &str) -> ConversionError {
+    ConversionError::UnsupportedValueType(format!("value '{name}': {reason}"))
+}
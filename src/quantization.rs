@@ -0,0 +1,316 @@
+//! Linear quantization of tensor data, following ONNX `QuantizeLinear` and
+//! `DequantizeLinear`:
+//!
+//! ```text
+//! q = saturate(round_half_to_even(x / scale) + zero_point)
+//! x = (q - zero_point) * scale
+//! ```
+//!
+//! Supported quantized types are `Int8`, `Uint8`, `Int4` and `Uint4`.
+
+use crate::data_types::DataType;
+use crate::error::{ConversionError, Result};
+use crate::tensor::Tensor;
+use crate::tensor_data::{PackedBits, TensorData};
+
+/// Which elements share a scale and zero point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    /// One scale for the whole tensor.
+    PerTensor,
+    /// One scale per index along `axis`.
+    PerAxis { axis: i64 },
+    /// One scale per `block_size` consecutive elements along `axis`; the
+    /// scale tensor has the input's shape with that dimension divided by
+    /// `block_size`, rounded up.
+    Blocked { axis: i64, block_size: usize },
+}
+
+/// Scales and zero points, flattened in row-major order of the scale tensor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantParams {
+    pub scale: Vec<f32>,
+    /// Same length as `scale`.
+    pub zero_point: Vec<i32>,
+    pub granularity: Granularity,
+}
+
+impl QuantParams {
+    pub fn per_tensor(scale: f32, zero_point: i32) -> Self {
+        Self {
+            scale: vec![scale],
+            zero_point: vec![zero_point],
+            granularity: Granularity::PerTensor,
+        }
+    }
+
+    pub fn per_axis(axis: i64, scale: Vec<f32>, zero_point: Vec<i32>) -> Self {
+        Self {
+            scale,
+            zero_point,
+            granularity: Granularity::PerAxis { axis },
+        }
+    }
+
+    pub fn blocked(axis: i64, block_size: usize, scale: Vec<f32>, zero_point: Vec<i32>) -> Self {
+        Self {
+            scale,
+            zero_point,
+            granularity: Granularity::Blocked { axis, block_size },
+        }
+    }
+
+    /// Builds parameters from the `y_scale`/`x_scale` and zero point inputs
+    /// and the `axis`/`block_size` attributes of a Q or DQ node. As in ONNX, a
+    /// positive `block_size` selects blocked quantization; otherwise the rank
+    /// of the scale decides between per-tensor and per-axis.
+    pub fn from_onnx(
+        input_shape: &[i64],
+        scale: &Tensor,
+        zero_point: Option<&Tensor>,
+        axis: i64,
+        block_size: i64,
+    ) -> Result<Self> {
+        let scale_values = scale.data().to_vec_f32()?;
+        let zero_point_values = match zero_point {
+            Some(zp) => {
+                if zp.shape() != scale.shape() {
+                    return Err(ConversionError::InvalidTensorData(format!(
+                        "zero point shape {:?} differs from scale shape {:?}",
+                        zp.shape(),
+                        scale.shape()
+                    )));
+                }
+                zp.data()
+                    .to_vec_i64()?
+                    .into_iter()
+                    .map(|z| z as i32)
+                    .collect()
+            }
+            None => vec![0; scale_values.len()],
+        };
+        let granularity = if block_size > 0 {
+            Granularity::Blocked {
+                axis,
+                block_size: block_size as usize,
+            }
+        } else if scale.element_count() == 1 && scale.rank() <= 1 {
+            Granularity::PerTensor
+        } else if scale.rank() == 1 {
+            Granularity::PerAxis { axis }
+        } else {
+            return Err(ConversionError::InvalidAttribute(format!(
+                "scale of shape {:?} for input {input_shape:?} needs a positive block_size",
+                scale.shape()
+            )));
+        };
+        let params = Self {
+            scale: scale_values,
+            zero_point: zero_point_values,
+            granularity,
+        };
+        params.indexing(input_shape)?;
+        Ok(params)
+    }
+
+    /// Per-tensor parameters mapping `[min, max]` onto the range of `dtype`.
+    ///
+    /// The range is widened to include zero so that zero is exactly
+    /// representable. With `symmetric`, it is also made symmetric around
+    /// zero, giving a zero point of 0 for signed types and the midpoint for
+    /// unsigned ones. An empty range gets a scale of 1.
+    pub fn from_min_max(min: f32, max: f32, dtype: &DataType, symmetric: bool) -> Result<Self> {
+        let (qmin, qmax) = quantized_range(dtype)?;
+        if !min.is_finite() || !max.is_finite() || min > max {
+            return Err(ConversionError::ValueOutOfRange(format!(
+                "cannot quantize the range [{min}, {max}]"
+            )));
+        }
+        let (mut min, mut max) = (min.min(0.0), max.max(0.0));
+        if symmetric {
+            let abs_max = min.abs().max(max);
+            (min, max) = (-abs_max, abs_max);
+        }
+
+        let scale = (max - min) / (qmax - qmin) as f32;
+        if scale == 0.0 || !scale.is_normal() {
+            return Ok(Self::per_tensor(1.0, 0));
+        }
+        let zero_point = if symmetric {
+            (qmin + qmax + 1) / 2
+        } else {
+            (qmin as f32 - min / scale).round_ties_even() as i32
+        };
+        Ok(Self::per_tensor(scale, zero_point.clamp(qmin, qmax)))
+    }
+
+    /// Checks the parameter count against `shape` and resolves how element
+    /// indices map to parameter indices.
+    fn indexing(&self, shape: &[i64]) -> Result<Indexing> {
+        if self.zero_point.len() != self.scale.len() {
+            return Err(ConversionError::InvalidTensorData(format!(
+                "{} zero points for {} scales",
+                self.zero_point.len(),
+                self.scale.len()
+            )));
+        }
+        let invalid = |message: String| {
+            ConversionError::InvalidTensorData(format!(
+                "{:?} with {} scales does not fit shape {shape:?}: {message}",
+                self.granularity,
+                self.scale.len()
+            ))
+        };
+        let (axis, block_size) = match self.granularity {
+            Granularity::PerTensor if self.scale.len() == 1 => return Ok(Indexing::PerTensor),
+            Granularity::PerTensor => return Err(invalid("expected one scale".to_string())),
+            Granularity::PerAxis { axis } => (axis, None),
+            Granularity::Blocked { axis, block_size } => (axis, Some(block_size)),
+        };
+
+        let rank = shape.len() as i64;
+        if !(-rank..rank).contains(&axis) {
+            return Err(invalid(format!("axis {axis} is out of range")));
+        }
+        let axis = axis.rem_euclid(rank) as usize;
+        let dim = shape[axis] as usize;
+        let inner = shape[axis + 1..].iter().product::<i64>() as usize;
+        let (indexing, expected) = match block_size {
+            None => (Indexing::PerAxis { dim, inner }, dim),
+            Some(0) => return Err(invalid("block_size must be positive".to_string())),
+            Some(block_size) => {
+                let blocks = dim.div_ceil(block_size);
+                let outer = shape[..axis].iter().product::<i64>() as usize;
+                let indexing = Indexing::Blocked {
+                    dim,
+                    inner,
+                    blocks,
+                    block_size,
+                };
+                (indexing, outer * blocks * inner)
+            }
+        };
+        if self.scale.len() != expected {
+            return Err(invalid(format!("expected {expected}")));
+        }
+        Ok(indexing)
+    }
+}
+
+/// Parameter lookup resolved against a concrete shape.
+#[derive(Clone, Copy)]
+enum Indexing {
+    PerTensor,
+    PerAxis {
+        dim: usize,
+        inner: usize,
+    },
+    Blocked {
+        dim: usize,
+        inner: usize,
+        blocks: usize,
+        block_size: usize,
+    },
+}
+
+impl Indexing {
+    fn param_index(self, i: usize) -> usize {
+        match self {
+            Indexing::PerTensor => 0,
+            Indexing::PerAxis { dim, inner } => (i / inner) % dim,
+            Indexing::Blocked {
+                dim,
+                inner,
+                blocks,
+                block_size,
+            } => {
+                let outer = i / (dim * inner);
+                let along = (i / inner) % dim;
+                (outer * blocks + along / block_size) * inner + i % inner
+            }
+        }
+    }
+}
+
+impl Tensor {
+    /// Quantizes a floating point tensor to `to`. NaN maps to the zero point.
+    pub fn quantize(&self, params: &QuantParams, to: DataType) -> Result<Tensor> {
+        if !self.dtype().is_floating() {
+            return Err(ConversionError::UnsupportedDataType(format!(
+                "cannot quantize {} data",
+                self.dtype()
+            )));
+        }
+        let (qmin, qmax) = quantized_range(&to)?;
+        let indexing = params.indexing(self.shape())?;
+        let values: Vec<i32> = self
+            .data()
+            .to_vec_f32()?
+            .into_iter()
+            .enumerate()
+            .map(|(i, x)| {
+                let p = indexing.param_index(i);
+                let q = (x / params.scale[p]).round_ties_even();
+                // `as` maps NaN to 0 and saturates infinities
+                (q as i32)
+                    .saturating_add(params.zero_point[p])
+                    .clamp(qmin, qmax)
+            })
+            .collect();
+
+        let data = match to {
+            DataType::Int8 => TensorData::Int8(values.iter().map(|&q| q as i8).collect()),
+            DataType::Uint8 => TensorData::Uint8(values.iter().map(|&q| q as u8).collect()),
+            DataType::Int4 => TensorData::Int4(PackedBits::pack_signed(
+                &values.iter().map(|&q| q as i8).collect::<Vec<_>>(),
+                4,
+            )),
+            _ => TensorData::Uint4(PackedBits::pack(
+                &values.iter().map(|&q| q as u8).collect::<Vec<_>>(),
+                4,
+            )),
+        };
+        Tensor::new(to, self.shape().to_vec(), data)
+    }
+
+    /// Dequantizes an `Int8`/`Uint8`/`Int4`/`Uint4` tensor to the float type
+    /// `to` (`Float32`, `Float16` or `BFloat16`).
+    pub fn dequantize(&self, params: &QuantParams, to: DataType) -> Result<Tensor> {
+        quantized_range(self.dtype())?;
+        let indexing = params.indexing(self.shape())?;
+        let values: Vec<f32> = self
+            .data()
+            .to_vec_i64()?
+            .into_iter()
+            .enumerate()
+            .map(|(i, q)| {
+                let p = indexing.param_index(i);
+                (q as i32 - params.zero_point[p]) as f32 * params.scale[p]
+            })
+            .collect();
+
+        let data = match to {
+            DataType::Float32 => TensorData::Float32(values),
+            DataType::Float16 => TensorData::float16_from_f32(&values),
+            DataType::BFloat16 => TensorData::bfloat16_from_f32(&values),
+            other => {
+                return Err(ConversionError::UnsupportedDataType(format!(
+                    "cannot dequantize to {other}"
+                )));
+            }
+        };
+        Tensor::new(to, self.shape().to_vec(), data)
+    }
+}
+
+fn quantized_range(dtype: &DataType) -> Result<(i32, i32)> {
+    match dtype {
+        DataType::Int8 => Ok((-128, 127)),
+        DataType::Uint8 => Ok((0, 255)),
+        DataType::Int4 => Ok((-8, 7)),
+        DataType::Uint4 => Ok((0, 15)),
+        other => Err(ConversionError::UnsupportedDataType(format!(
+            "{other} is not a supported quantized type"
+        ))),
+    }
+}
@@ -0,0 +1,250 @@
+//! The safetensors weight format: an 8-byte little-endian header length, a
+//! JSON header describing each tensor, then one contiguous byte buffer.
+//!
+//! Headers use the standard safetensors dtype codes (`F32`, `BF16`, ...);
+//! when reading, this crate's [`DataType`] names (`float32`, ...) are accepted
+//! as well. Sub-byte types and the FNUZ float8 variants have no code and
+//! cannot be stored.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+use serde_json::{Map, Value as JsonValue, json};
+
+use crate::data_types::DataType;
+use crate::error::{ConversionError, Result};
+use crate::protos::onnx::TensorProto;
+use crate::tensor::Tensor;
+use crate::tensor_data::{TensorData, element_count_of};
+
+/// Headers larger than this are rejected instead of being allocated.
+pub const MAX_HEADER_SIZE: u64 = 100_000_000;
+
+const METADATA_KEY: &str = "__metadata__";
+
+/// The contents of a safetensors file.
+#[derive(Debug, Clone, Default)]
+pub struct Safetensors {
+    /// Tensors in the order their bytes appear in the file.
+    pub tensors: Vec<(String, Tensor)>,
+    /// The free-form `__metadata__` string map.
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Safetensors {
+    /// Collects the initializers of an ONNX graph. External initializers must
+    /// be inlined first, see [`crate::external_data::ExternalDataResolver`].
+    pub fn from_initializers(initializers: &[TensorProto]) -> Result<Self> {
+        let tensors = initializers
+            .iter()
+            .map(|init| Ok((init.name.clone(), Tensor::from_tensor_proto(init)?)))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            tensors,
+            metadata: BTreeMap::new(),
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Tensor> {
+        self.tensors
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, tensor)| tensor)
+    }
+
+    /// Reads a whole safetensors file. The byte ranges of the tensors must
+    /// cover the buffer exactly, without gaps or overlaps. Each range is read
+    /// into its own buffer, so the file is never held in memory twice.
+    pub fn read(mut reader: impl Read) -> Result<Self> {
+        let mut len = [0u8; 8];
+        reader.read_exact(&mut len)?;
+        let header_len = u64::from_le_bytes(len);
+        if header_len > MAX_HEADER_SIZE {
+            return Err(invalid(format!(
+                "header of {header_len} bytes exceeds the {MAX_HEADER_SIZE} byte limit"
+            )));
+        }
+        let mut header = vec![0u8; header_len as usize];
+        reader.read_exact(&mut header)?;
+        let header: Map<String, JsonValue> = serde_json::from_slice(&header)
+            .map_err(|e| invalid(format!("header is not a JSON object: {e}")))?;
+
+        let mut metadata = BTreeMap::new();
+        let mut entries = Vec::new();
+        for (name, info) in header {
+            if name == METADATA_KEY {
+                metadata = serde_json::from_value(info)
+                    .map_err(|e| invalid(format!("{METADATA_KEY} must map strings: {e}")))?;
+                continue;
+            }
+            let (dtype, shape, begin, end) = parse_entry(&name, &info)?;
+            entries.push((begin, end, name, dtype, shape));
+        }
+
+        // read each range straight into its own buffer, in file order
+        // by end as well, so empty tensors come before the one starting there
+        entries.sort_by_key(|&(begin, end, ..)| (begin, end));
+        let mut expected_begin = 0;
+        let mut tensors = Vec::with_capacity(entries.len());
+        for (begin, end, name, dtype, shape) in entries {
+            if begin != expected_begin || end < begin {
+                return Err(invalid(format!(
+                    "tensor '{name}' has data_offsets [{begin}, {end}], expected to start at \
+                     {expected_begin}"
+                )));
+            }
+            let size = dtype.storage_len(element_count_of(&shape)?);
+            if end - begin != size {
+                return Err(invalid(format!(
+                    "tensor '{name}' ({dtype} {shape:?}) needs {size} bytes, has {}",
+                    end - begin
+                )));
+            }
+            let mut bytes = Vec::new();
+            bytes
+                .try_reserve_exact(size)
+                .map_err(|e| invalid(format!("tensor '{name}' of {size} bytes: {e}")))?;
+            reader.by_ref().take(size as u64).read_to_end(&mut bytes)?;
+            if bytes.len() != size {
+                return Err(invalid(format!(
+                    "tensor '{name}' ends at byte {end} but the buffer ends at {}",
+                    begin + bytes.len()
+                )));
+            }
+            tensors.push((name, Tensor::new(dtype, shape, TensorData::Raw(bytes))?));
+            expected_begin = end;
+        }
+        if reader.read(&mut [0u8])? != 0 {
+            return Err(invalid(format!(
+                "trailing bytes after the last tensor, which ends at {expected_begin}"
+            )));
+        }
+        Ok(Self { tensors, metadata })
+    }
+
+    /// Writes the tensors, widest element type first so every tensor stays
+    /// naturally aligned in the buffer.
+    pub fn write(&self, mut writer: impl Write) -> Result<()> {
+        let mut order: Vec<&(String, Tensor)> = self.tensors.iter().collect();
+        order.sort_by_key(|(_, tensor)| std::cmp::Reverse(tensor.dtype().byte_size()));
+
+        let mut header = Map::new();
+        if !self.metadata.is_empty() {
+            header.insert(METADATA_KEY.to_string(), json!(self.metadata));
+        }
+        let mut offset = 0;
+        for (name, tensor) in &order {
+            let size = tensor.data().byte_len();
+            let info = json!({
+                "dtype": dtype_code(tensor.dtype())?,
+                "shape": tensor.shape(),
+                "data_offsets": [offset, offset + size],
+            });
+            if name == METADATA_KEY || header.insert(name.clone(), info).is_some() {
+                return Err(invalid(format!(
+                    "duplicate or reserved tensor name '{name}'"
+                )));
+            }
+            offset += size;
+        }
+
+        let mut header = serde_json::to_string(&header)
+            .map_err(|e| ConversionError::Internal(format!("safetensors header: {e}")))?;
+        // the buffer starts 8-byte aligned
+        header.extend(std::iter::repeat_n(
+            ' ',
+            header.len().next_multiple_of(8) - header.len(),
+        ));
+
+        writer.write_all(&(header.len() as u64).to_le_bytes())?;
+        writer.write_all(header.as_bytes())?;
+        for (_, tensor) in order {
+            writer.write_all(&tensor.data().as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_entry(name: &str, info: &JsonValue) -> Result<(DataType, Vec<i64>, usize, usize)> {
+    let field = |key: &str| {
+        info.get(key)
+            .ok_or_else(|| invalid(format!("tensor '{name}' has no '{key}'")))
+    };
+    let dtype = field("dtype")?
+        .as_str()
+        .ok_or_else(|| invalid(format!("tensor '{name}' has a non-string dtype")))?;
+    let dtype = parse_dtype(dtype)?;
+    let shape = field("shape")?
+        .as_array()
+        .and_then(|dims| dims.iter().map(|d| d.as_i64()).collect::<Option<Vec<_>>>())
+        .ok_or_else(|| invalid(format!("tensor '{name}' has an invalid shape")))?;
+    let offsets = field("data_offsets")?
+        .as_array()
+        .and_then(|o| o.iter().map(|x| x.as_u64()).collect::<Option<Vec<_>>>())
+        .filter(|o| o.len() == 2)
+        .ok_or_else(|| invalid(format!("tensor '{name}' has invalid data_offsets")))?;
+    Ok((dtype, shape, offsets[0] as usize, offsets[1] as usize))
+}
+
+fn dtype_code(dtype: &DataType) -> Result<&'static str> {
+    Ok(match dtype {
+        DataType::Bool => "BOOL",
+        DataType::Uint8 => "U8",
+        DataType::Int8 => "I8",
+        DataType::Uint16 => "U16",
+        DataType::Int16 => "I16",
+        DataType::Uint32 => "U32",
+        DataType::Int32 => "I32",
+        DataType::Uint64 => "U64",
+        DataType::Int64 => "I64",
+        DataType::Float16 => "F16",
+        DataType::BFloat16 => "BF16",
+        DataType::Float32 => "F32",
+        DataType::Float64 => "F64",
+        DataType::Float8E4M3FN => "F8_E4M3",
+        DataType::Float8E5M2 => "F8_E5M2",
+        DataType::Float8E8M0 => "F8_E8M0",
+        other => {
+            return Err(ConversionError::UnsupportedDataType(format!(
+                "{other} has no safetensors dtype"
+            )));
+        }
+    })
+}
+
+fn parse_dtype(code: &str) -> Result<DataType> {
+    Ok(match code {
+        "BOOL" => DataType::Bool,
+        "U8" => DataType::Uint8,
+        "I8" => DataType::Int8,
+        "U16" => DataType::Uint16,
+        "I16" => DataType::Int16,
+        "U32" => DataType::Uint32,
+        "I32" => DataType::Int32,
+        "U64" => DataType::Uint64,
+        "I64" => DataType::Int64,
+        "F16" => DataType::Float16,
+        "BF16" => DataType::BFloat16,
+        "F32" => DataType::Float32,
+        "F64" => DataType::Float64,
+        "F8_E4M3" => DataType::Float8E4M3FN,
+        "F8_E5M2" => DataType::Float8E5M2,
+        "F8_E8M0" => DataType::Float8E8M0,
+        name => {
+            let dtype: DataType = serde_json::from_value(JsonValue::from(name)).map_err(|_| {
+                ConversionError::UnsupportedDataType(format!("safetensors dtype '{name}'"))
+            })?;
+            // only types that `write` can store as well
+            if dtype_code(&dtype).is_err() {
+                return Err(ConversionError::UnsupportedDataType(format!(
+                    "safetensors dtype '{name}'"
+                )));
+            }
+            dtype
+        }
+    })
+}
+
+fn invalid(message: String) -> ConversionError {
+    ConversionError::InvalidTensorData(format!("safetensors: {message}"))
+}
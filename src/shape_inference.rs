@@ -6,7 +6,7 @@ pub enum Dim {
     Dynamic(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TensorShape {
     pub dims: Vec<Dim>,
 }
@@ -69,6 +69,11 @@ impl ShapeInferenceContext {
         self.value_shapes.get(name)
     }
 
+    /// Shape of `name` with dynamic dimensions resolved from the overrides.
+    pub fn get_static_shape(&self, name: &str) -> Option<Vec<i64>> {
+        self.get_shape(name)?.to_static(&self.overrides)
+    }
+
     pub fn infer_unary_op(&self, input: &str) -> Option<TensorShape> {
         self.get_shape(input).cloned()
     }
@@ -0,0 +1,323 @@
+//! Lists the initializers of a serialized `ModelProto` by walking the
+//! protobuf wire format, without decoding the model or any tensor payload.
+//!
+//! Only `ModelProto.graph` (field 7) and its `initializer`s (field 5) are
+//! entered; everything else, including node lists and the payloads
+//! themselves, is skipped by seeking. Each initializer reports where its
+//! payload lies in the file so that it can be read later, one at a time.
+
+use std::io::{BufReader, Read, Seek, SeekFrom};
+
+use prost::Message;
+use prost::encoding::{WireType, encode_key, encode_varint};
+
+use crate::error::{ConversionError, Result};
+use crate::protos::onnx::{StringStringEntryProto, TensorProto};
+use crate::tensor::Tensor;
+
+const MODEL_GRAPH: u32 = 7;
+const GRAPH_INITIALIZER: u32 = 5;
+
+/// The `TensorProto` field holding an initializer's payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadField {
+    RawData,
+    FloatData,
+    Int32Data,
+    Int64Data,
+    DoubleData,
+    Uint64Data,
+}
+
+impl PayloadField {
+    fn from_tag(tag: u32) -> Option<Self> {
+        match tag {
+            9 => Some(Self::RawData),
+            4 => Some(Self::FloatData),
+            5 => Some(Self::Int32Data),
+            7 => Some(Self::Int64Data),
+            10 => Some(Self::DoubleData),
+            11 => Some(Self::Uint64Data),
+            _ => None,
+        }
+    }
+
+    pub fn tag(self) -> u32 {
+        match self {
+            Self::RawData => 9,
+            Self::FloatData => 4,
+            Self::Int32Data => 5,
+            Self::Int64Data => 7,
+            Self::DoubleData => 10,
+            Self::Uint64Data => 11,
+        }
+    }
+}
+
+/// Byte range of a payload within the model file. For the typed fields
+/// this is the packed encoding, e.g. little-endian `f32`s for `FloatData`
+/// and varints for `Int64Data`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadLocation {
+    pub field: PayloadField,
+    pub offset: u64,
+    pub length: u64,
+}
+
+/// Everything about an initializer except its payload.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InitializerInfo {
+    pub name: String,
+    pub data_type: i32,
+    pub dims: Vec<i64>,
+    /// `None` for tensors without inline data, such as external ones.
+    pub payload: Option<PayloadLocation>,
+    pub data_location: i32,
+    pub external_data: Vec<StringStringEntryProto>,
+}
+
+impl InitializerInfo {
+    /// Reads the payload from `model` (the file the info was read from) and
+    /// assembles the full `TensorProto`.
+    pub fn read_tensor_proto(&self, mut model: impl Read + Seek) -> Result<TensorProto> {
+        let mut message = TensorProto {
+            name: self.name.clone(),
+            data_type: self.data_type,
+            dims: self.dims.clone(),
+            data_location: self.data_location,
+            external_data: self.external_data.clone(),
+            ..Default::default()
+        }
+        .encode_to_vec();
+        if let Some(payload) = self.payload {
+            model.seek(SeekFrom::Start(payload.offset))?;
+            encode_key(payload.field.tag(), WireType::LengthDelimited, &mut message);
+            encode_varint(payload.length, &mut message);
+            let start = message.len();
+            message.resize(start + payload.length as usize, 0);
+            model.read_exact(&mut message[start..])?;
+        }
+        TensorProto::decode(message.as_slice()).map_err(|e| {
+            ConversionError::InvalidTensorData(format!("initializer '{}': {e}", self.name))
+        })
+    }
+
+    /// Reads and decodes the payload; see [`InitializerInfo::read_tensor_proto`].
+    pub fn read_tensor(&self, model: impl Read + Seek) -> Result<Tensor> {
+        Tensor::from_tensor_proto(&self.read_tensor_proto(model)?)
+    }
+}
+
+/// Iterates over the initializers of a serialized `ModelProto`.
+///
+/// ```no_run
+/// # use webnn_onnx_utils::streaming::InitializerReader;
+/// let file = std::fs::File::open("model.onnx")?;
+/// for info in InitializerReader::new(file)? {
+///     let info = info?;
+///     println!("{} {:?} {:?}", info.name, info.dims, info.payload);
+/// }
+/// # Ok::<(), webnn_onnx_utils::ConversionError>(())
+/// ```
+pub struct InitializerReader<R> {
+    reader: BufReader<R>,
+    position: u64,
+    model_end: u64,
+    /// End of the `GraphProto` being walked, if inside one.
+    graph_end: Option<u64>,
+    failed: bool,
+}
+
+impl<R: Read + Seek> InitializerReader<R> {
+    pub fn new(mut reader: R) -> Result<Self> {
+        let model_end = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(0))?;
+        Ok(Self {
+            reader: BufReader::new(reader),
+            position: 0,
+            model_end,
+            graph_end: None,
+            failed: false,
+        })
+    }
+
+    fn next_initializer(&mut self) -> Result<Option<InitializerInfo>> {
+        loop {
+            if let Some(graph_end) = self.graph_end {
+                if self.position >= graph_end {
+                    self.graph_end = None;
+                    continue;
+                }
+                let (field, wire_type) = self.read_key()?;
+                if field == GRAPH_INITIALIZER && wire_type == WireType::LengthDelimited {
+                    let end = self.read_length(graph_end)?;
+                    return self.read_initializer(end).map(Some);
+                }
+                self.skip(wire_type, graph_end)?;
+            } else {
+                if self.position >= self.model_end {
+                    return Ok(None);
+                }
+                let (field, wire_type) = self.read_key()?;
+                if field == MODEL_GRAPH && wire_type == WireType::LengthDelimited {
+                    self.graph_end = Some(self.read_length(self.model_end)?);
+                } else {
+                    self.skip(wire_type, self.model_end)?;
+                }
+            }
+        }
+    }
+
+    fn read_initializer(&mut self, end: u64) -> Result<InitializerInfo> {
+        let mut info = InitializerInfo::default();
+        while self.position < end {
+            let (field, wire_type) = self.read_key()?;
+            match (field, wire_type) {
+                (1, WireType::Varint) => info.dims.push(self.read_varint()? as i64),
+                (1, WireType::LengthDelimited) => {
+                    let packed_end = self.read_length(end)?;
+                    while self.position < packed_end {
+                        info.dims.push(self.read_varint()? as i64);
+                    }
+                }
+                (2, WireType::Varint) => info.data_type = self.read_varint()? as i32,
+                (8, WireType::LengthDelimited) => {
+                    let bytes = self.read_message(end)?;
+                    info.name = String::from_utf8(bytes).map_err(|_| {
+                        ConversionError::InvalidTensorData(
+                            "initializer name is not UTF-8".to_string(),
+                        )
+                    })?;
+                }
+                (13, WireType::LengthDelimited) => {
+                    let bytes = self.read_message(end)?;
+                    let entry = StringStringEntryProto::decode(bytes.as_slice()).map_err(|e| {
+                        ConversionError::InvalidTensorData(format!("external_data entry: {e}"))
+                    })?;
+                    info.external_data.push(entry);
+                }
+                (14, WireType::Varint) => info.data_location = self.read_varint()? as i32,
+                (tag, wire_type) => match PayloadField::from_tag(tag) {
+                    Some(field) => {
+                        if wire_type != WireType::LengthDelimited
+                            || (field != PayloadField::RawData && info.payload.is_some())
+                        {
+                            return Err(ConversionError::InvalidTensorData(format!(
+                                "initializer '{}' has {field:?} that is not in one packed range",
+                                info.name
+                            )));
+                        }
+                        let payload_end = self.read_length(end)?;
+                        info.payload = Some(PayloadLocation {
+                            field,
+                            offset: self.position,
+                            length: payload_end - self.position,
+                        });
+                        self.seek_to(payload_end)?;
+                    }
+                    None => self.skip(wire_type, end)?,
+                },
+            }
+        }
+        if self.position != end {
+            return Err(truncated(end));
+        }
+        Ok(info)
+    }
+
+    fn read_byte(&mut self) -> Result<u8> {
+        let mut byte = [0u8];
+        self.reader.read_exact(&mut byte)?;
+        self.position += 1;
+        Ok(byte[0])
+    }
+
+    fn read_varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ConversionError::InvalidTensorData(
+            "varint longer than 10 bytes".to_string(),
+        ))
+    }
+
+    fn read_key(&mut self) -> Result<(u32, WireType)> {
+        let key = self.read_varint()?;
+        let wire_type = WireType::try_from(key & 0x7).map_err(|_| {
+            ConversionError::InvalidTensorData(format!("invalid wire type in key {key}"))
+        })?;
+        Ok(((key >> 3) as u32, wire_type))
+    }
+
+    /// Reads the length of a length-delimited field and returns where the
+    /// field ends, which must not be past `end`.
+    fn read_length(&mut self, end: u64) -> Result<u64> {
+        let length = self.read_varint()?;
+        self.position
+            .checked_add(length)
+            .filter(|&field_end| field_end <= end)
+            .ok_or_else(|| truncated(end))
+    }
+
+    /// Reads a whole length-delimited field.
+    fn read_message(&mut self, end: u64) -> Result<Vec<u8>> {
+        let field_end = self.read_length(end)?;
+        let mut bytes = vec![0; (field_end - self.position) as usize];
+        self.reader.read_exact(&mut bytes)?;
+        self.position = field_end;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, wire_type: WireType, end: u64) -> Result<()> {
+        let target = match wire_type {
+            WireType::Varint => {
+                self.read_varint()?;
+                return Ok(());
+            }
+            WireType::SixtyFourBit => self.position + 8,
+            WireType::ThirtyTwoBit => self.position + 4,
+            WireType::LengthDelimited => self.read_length(end)?,
+            WireType::StartGroup | WireType::EndGroup => {
+                return Err(ConversionError::InvalidTensorData(
+                    "protobuf groups are not supported".to_string(),
+                ));
+            }
+        };
+        if target > end {
+            return Err(truncated(end));
+        }
+        self.seek_to(target)
+    }
+
+    fn seek_to(&mut self, target: u64) -> Result<()> {
+        // seek_relative keeps the buffer when the target is already in it
+        self.reader
+            .seek_relative(target as i64 - self.position as i64)?;
+        self.position = target;
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> Iterator for InitializerReader<R> {
+    type Item = Result<InitializerInfo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let next = self.next_initializer().transpose();
+        self.failed = matches!(next, Some(Err(_)));
+        next
+    }
+}
+
+fn truncated(end: u64) -> ConversionError {
+    ConversionError::InvalidTensorData(format!(
+        "protobuf field runs past the end of its message at byte {end}"
+    ))
+}
@@ -0,0 +1,194 @@
+//! A [`TensorData`] payload together with its data type and shape.
+
+use crate::data_types::DataType;
+use crate::error::{ConversionError, Result};
+use crate::protos::onnx::TensorProto;
+use crate::tensor_data::{
+    Scalar, TensorData, TensorView, element_bytes, element_count_of, element_width,
+    from_element_bytes,
+};
+
+/// A typed payload that is known to match `dtype` and `shape`.
+///
+/// Unlike [`TensorData::to_tensor_proto`], converting a `Tensor` to a proto
+/// cannot fail: the checks happen once, when the tensor is built.
+#[derive(Debug, Clone)]
+pub struct Tensor {
+    dtype: DataType,
+    shape: Vec<i64>,
+    data: TensorData,
+}
+
+impl Tensor {
+    /// Attaches `dtype` and `shape` to `data`, decoding `Raw` bytes.
+    pub fn new(dtype: DataType, shape: Vec<i64>, data: TensorData) -> Result<Self> {
+        data.check_shape("", &dtype, &shape)?;
+        let data = match data {
+            TensorData::Raw(raw) => TensorView::new("", dtype.clone(), &shape, &raw)?.decode()?,
+            typed => typed,
+        };
+        Ok(Self { dtype, shape, data })
+    }
+
+    /// A tensor of `shape` with every element set to `value`.
+    pub fn filled(dtype: DataType, shape: Vec<i64>, value: f32) -> Result<Self> {
+        element_count_of(&shape)?;
+        let data = TensorData::filled(dtype.clone(), &shape, value);
+        Ok(Self { dtype, shape, data })
+    }
+
+    /// A rank-0 tensor holding `value`.
+    pub fn scalar(dtype: DataType, value: f32) -> Self {
+        let data = TensorData::scalar(dtype.clone(), value);
+        Self {
+            dtype,
+            shape: vec![],
+            data,
+        }
+    }
+
+    /// Reads `tensor`, requiring its payload to match its dims.
+    pub fn from_tensor_proto(tensor: &TensorProto) -> Result<Self> {
+        let dtype = crate::data_types::onnx_to_webnn(tensor.data_type)?;
+        let data = TensorData::from_tensor_proto(tensor)?;
+        Self::new(dtype, tensor.dims.clone(), data)
+    }
+
+    pub fn dtype(&self) -> &DataType {
+        &self.dtype
+    }
+
+    pub fn shape(&self) -> &[i64] {
+        &self.shape
+    }
+
+    pub fn data(&self) -> &TensorData {
+        &self.data
+    }
+
+    pub fn into_data(self) -> TensorData {
+        self.data
+    }
+
+    pub fn element_count(&self) -> usize {
+        // shape was validated in new()
+        self.shape.iter().product::<i64>() as usize
+    }
+
+    pub fn rank(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// Row-major strides, in elements.
+    pub fn strides(&self) -> Vec<usize> {
+        let mut strides = vec![1; self.shape.len()];
+        for i in (0..self.shape.len().saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * self.shape[i + 1] as usize;
+        }
+        strides
+    }
+
+    /// Reinterprets the elements with a new shape, following ONNX `Reshape`:
+    /// one dimension may be `-1` to infer it, and `0` copies the dimension at
+    /// the same position.
+    pub fn reshape(self, shape: &[i64]) -> Result<Self> {
+        let invalid = |message: String| {
+            ConversionError::InvalidTensorData(format!(
+                "cannot reshape {:?} to {shape:?}: {message}",
+                self.shape
+            ))
+        };
+        let mut resolved = shape
+            .iter()
+            .enumerate()
+            .map(|(i, &d)| match d {
+                0 => self
+                    .shape
+                    .get(i)
+                    .copied()
+                    .ok_or_else(|| invalid(format!("no dimension {i} to copy"))),
+                d if d < -1 => Err(invalid(format!("invalid dimension {d}"))),
+                d => Ok(d),
+            })
+            .collect::<Result<Vec<i64>>>()?;
+
+        let numel = self.element_count();
+        match resolved.iter().filter(|&&d| d == -1).count() {
+            0 => {}
+            1 => {
+                let known =
+                    element_count_of(&resolved.iter().map(|&d| d.max(1)).collect::<Vec<_>>())?;
+                if known == 0 || !numel.is_multiple_of(known) {
+                    return Err(invalid(format!("{numel} elements cannot be split evenly")));
+                }
+                let inferred = (numel / known) as i64;
+                resolved
+                    .iter_mut()
+                    .filter(|d| **d == -1)
+                    .for_each(|d| *d = inferred);
+            }
+            _ => return Err(invalid("more than one -1".to_string())),
+        }
+
+        if element_count_of(&resolved)? != numel {
+            return Err(invalid(format!("element count must stay {numel}")));
+        }
+        Ok(Self {
+            shape: resolved,
+            ..self
+        })
+    }
+
+    /// Row-major position of `index`, or `None` if it has the wrong rank or is
+    /// out of bounds.
+    pub fn flat_index(&self, index: &[usize]) -> Option<usize> {
+        if index.len() != self.shape.len() {
+            return None;
+        }
+        index
+            .iter()
+            .zip(&self.shape)
+            .try_fold(0usize, |acc, (&i, &d)| {
+                (i < d as usize).then(|| acc * d as usize + i)
+            })
+    }
+
+    /// Element at the N-dimensional `index`.
+    pub fn get(&self, index: &[usize]) -> Option<Scalar> {
+        self.data.get(self.flat_index(index)?)
+    }
+
+    /// Builds a tensor of `shape` from the elements at the `flat` positions.
+    pub(crate) fn take(&self, flat: &[usize], shape: Vec<i64>) -> Result<Self> {
+        let width = element_width(&self.dtype);
+        let source = element_bytes(&self.data, &self.dtype, self.element_count())?;
+        let mut bytes = Vec::with_capacity(flat.len() * width);
+        for &i in flat {
+            bytes.extend_from_slice(&source[i * width..(i + 1) * width]);
+        }
+        Self::new(
+            self.dtype.clone(),
+            shape,
+            from_element_bytes(&bytes, &self.dtype),
+        )
+    }
+
+    pub fn to_tensor_proto(&self, name: String) -> TensorProto {
+        self.data
+            .to_tensor_proto(name, self.dtype.clone(), self.shape.clone())
+            .expect("payload was validated in Tensor::new")
+    }
+
+    pub fn into_tensor_proto(self, name: String) -> TensorProto {
+        self.data
+            .into_tensor_proto(name, self.dtype, self.shape)
+            .expect("payload was validated in Tensor::new")
+    }
+}
+
+impl TensorData {
+    /// Attaches `dtype` and `shape`; see [`Tensor::new`].
+    pub fn attach(self, dtype: DataType, shape: Vec<i64>) -> Result<Tensor> {
+        Tensor::new(dtype, shape, self)
+    }
+}
@@ -1,6 +1,11 @@
-use crate::data_types::{DataType, onnx_proto_to_webnn};
+use std::borrow::Cow;
+
+use crate::data_types::{DataType, TypeLoweringPolicy, onnx_proto_to_webnn};
 use crate::error::{ConversionError, Result};
-use crate::protos::onnx::TensorProto;
+use crate::half_float::HalfFormat;
+use crate::minifloat::{self, MiniFloatFormat};
+use crate::protos::onnx::tensor_proto::DataLocation;
+use crate::protos::onnx::{SparseTensorProto, TensorProto};
 
 #[derive(Debug, Clone)]
 pub enum TensorData {
@@ -13,14 +18,340 @@ pub enum TensorData {
     Uint64(Vec<u64>),
     Uint32(Vec<u32>),
     Uint8(Vec<u8>),
+    Bool(Vec<bool>),
+    Int16(Vec<i16>),
+    Uint16(Vec<u16>),
+    Float64(Vec<f64>),
+    BFloat16(Vec<u16>), // Raw bits representation
+    Float8E4M3FN(Vec<u8>),
+    Float8E4M3FNUZ(Vec<u8>),
+    Float8E5M2(Vec<u8>),
+    Float8E5M2FNUZ(Vec<u8>),
+    Float8E8M0(Vec<u8>),
+    Float4E2M1(PackedBits),
+    Int4(PackedBits),
+    Uint4(PackedBits),
+    Int2(PackedBits),
+    Uint2(PackedBits),
+}
+
+/// Sub-byte elements packed LSB first, the layout ONNX uses for 4-bit and
+/// 2-bit types. `len` is the element count, so odd lengths are unambiguous.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedBits {
+    pub bytes: Vec<u8>,
+    pub len: usize,
+}
+
+impl PackedBits {
+    /// Packs the low `bit_width` bits of each code; the unused high bits of the
+    /// last byte are zero.
+    pub fn pack(codes: &[u8], bit_width: usize) -> Self {
+        let per_byte = 8 / bit_width;
+        let mask = (1u8 << bit_width) - 1;
+        let bytes = codes
+            .chunks(per_byte)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u8, |acc, (i, &c)| acc | ((c & mask) << (i * bit_width)))
+            })
+            .collect();
+        Self {
+            bytes,
+            len: codes.len(),
+        }
+    }
+
+    /// Packs two's complement values; each must fit in `bit_width` bits.
+    pub fn pack_signed(values: &[i8], bit_width: usize) -> Self {
+        let codes: Vec<u8> = values.iter().map(|&v| v as u8).collect();
+        Self::pack(&codes, bit_width)
+    }
+
+    /// Returns one zero-extended code per element.
+    pub fn unpack(&self, bit_width: usize) -> Vec<u8> {
+        let per_byte = 8 / bit_width;
+        let mask = (1u8 << bit_width) - 1;
+        (0..self.len)
+            .map(|i| (self.bytes[i / per_byte] >> ((i % per_byte) * bit_width)) & mask)
+            .collect()
+    }
+
+    /// Zero-extended code of element `index`.
+    pub fn get(&self, index: usize, bit_width: usize) -> Option<u8> {
+        if index >= self.len {
+            return None;
+        }
+        let per_byte = 8 / bit_width;
+        let mask = (1u8 << bit_width) - 1;
+        Some((self.bytes[index / per_byte] >> ((index % per_byte) * bit_width)) & mask)
+    }
+
+    /// Returns one sign-extended value per element.
+    pub fn unpack_signed(&self, bit_width: usize) -> Vec<i8> {
+        let shift = 8 - bit_width;
+        self.unpack(bit_width)
+            .into_iter()
+            .map(|c| ((c << shift) as i8) >> shift)
+            .collect()
+    }
+
+    fn from_int32_data(tensor: &TensorProto, bit_width: usize) -> Self {
+        let bytes = int32_as_bytes(tensor);
+        let numel = tensor.dims.iter().product::<i64>().max(0) as usize;
+        let len = numel.min(bytes.len() * (8 / bit_width));
+        Self { bytes, len }
+    }
+}
+
+/// How [`TensorData::from_tensor_proto_with`] checks the payload size against
+/// `TensorProto.dims`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CountValidation {
+    /// The payload must hold exactly the number of elements the dims describe.
+    #[default]
+    Strict,
+    /// Like `Strict`, but an empty payload is accepted for any shape, for
+    /// initializers that are deliberately left without data.
+    AllowEmpty,
+}
+
+/// Number of elements described by `dims`; a scalar (no dims) has one.
+pub fn element_count_of(dims: &[i64]) -> Result<usize> {
+    dims.iter().try_fold(1usize, |acc, &d| {
+        let d = usize::try_from(d).map_err(|_| {
+            ConversionError::InvalidTensorData(format!("negative dimension {d} in {dims:?}"))
+        })?;
+        acc.checked_mul(d).ok_or_else(|| {
+            ConversionError::InvalidTensorData(format!("element count of {dims:?} overflows"))
+        })
+    })
+}
+
+/// A single element read with [`TensorData::get`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scalar {
+    Float(f64),
+    Int(i64),
+    Uint(u64),
+    Bool(bool),
+}
+
+impl Scalar {
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Scalar::Float(v) => v,
+            Scalar::Int(v) => v as f64,
+            Scalar::Uint(v) => v as f64,
+            Scalar::Bool(v) => v as u8 as f64,
+        }
+    }
+
+    /// The value as `i64`, or `None` for floats and unsigned values above
+    /// `i64::MAX`.
+    pub fn as_i64(self) -> Option<i64> {
+        match self {
+            Scalar::Float(_) => None,
+            Scalar::Int(v) => Some(v),
+            Scalar::Uint(v) => i64::try_from(v).ok(),
+            Scalar::Bool(v) => Some(v as i64),
+        }
+    }
+}
+
+/// Element types with their own [`TensorData`] variant, for
+/// [`TensorData::as_slice`].
+///
+/// `u16` and `u8` only match `Uint16` and `Uint8`; the bits of `Float16`,
+/// `BFloat16` and the 8-bit floats are not exposed as integers.
+pub trait TensorElement: Sized {
+    fn slice(data: &TensorData) -> Option<&[Self]>;
+}
+
+impl TensorElement for f32 {
+    fn slice(data: &TensorData) -> Option<&[Self]> {
+        match data {
+            TensorData::Float32(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for f64 {
+    fn slice(data: &TensorData) -> Option<&[Self]> {
+        match data {
+            TensorData::Float64(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for i64 {
+    fn slice(data: &TensorData) -> Option<&[Self]> {
+        match data {
+            TensorData::Int64(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for i32 {
+    fn slice(data: &TensorData) -> Option<&[Self]> {
+        match data {
+            TensorData::Int32(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for i16 {
+    fn slice(data: &TensorData) -> Option<&[Self]> {
+        match data {
+            TensorData::Int16(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for i8 {
+    fn slice(data: &TensorData) -> Option<&[Self]> {
+        match data {
+            TensorData::Int8(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for u64 {
+    fn slice(data: &TensorData) -> Option<&[Self]> {
+        match data {
+            TensorData::Uint64(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for u32 {
+    fn slice(data: &TensorData) -> Option<&[Self]> {
+        match data {
+            TensorData::Uint32(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for u16 {
+    fn slice(data: &TensorData) -> Option<&[Self]> {
+        match data {
+            TensorData::Uint16(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for u8 {
+    fn slice(data: &TensorData) -> Option<&[Self]> {
+        match data {
+            TensorData::Uint8(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for bool {
+    fn slice(data: &TensorData) -> Option<&[Self]> {
+        match data {
+            TensorData::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
 }
 
 impl TensorData {
+    /// Reads the payload of `tensor`, requiring its size to match `dims`.
     pub fn from_tensor_proto(tensor: &TensorProto) -> Result<Self> {
+        Self::from_tensor_proto_with(tensor, CountValidation::Strict)
+    }
+
+    pub fn from_tensor_proto_with(
+        tensor: &TensorProto,
+        validation: CountValidation,
+    ) -> Result<Self> {
         let proto = crate::protos::onnx::tensor_proto::DataType::try_from(tensor.data_type)
             .map_err(|_| ConversionError::UnsupportedOnnxDataType(tensor.data_type))?;
         let dtype = onnx_proto_to_webnn(proto)?;
 
+        if tensor.data_location == DataLocation::External as i32 {
+            return Err(ConversionError::InvalidTensorData(format!(
+                "tensor '{}' stores its data externally; load it with ExternalDataResolver",
+                tensor.name
+            )));
+        }
+
+        let data = Self::read_payload(tensor, &dtype)?;
+        data.validate_count(tensor, &dtype, validation)?;
+        Ok(data)
+    }
+
+    /// Densifies a COO `SparseTensorProto`. `indices` may be linearized
+    /// (`[NNZ]`) or hold one coordinate per dimension (`[NNZ, rank]`); either
+    /// way they must be strictly ascending. Unlisted elements are zero.
+    pub fn from_sparse_tensor_proto(sparse: &SparseTensorProto) -> Result<Self> {
+        let values = sparse.values.as_ref().ok_or_else(|| {
+            ConversionError::InvalidTensorData("sparse tensor has no values".to_string())
+        })?;
+        let dtype = crate::data_types::onnx_to_webnn(values.data_type)?;
+        let nnz = match values.dims.as_slice() {
+            [nnz] => usize::try_from(*nnz).unwrap_or(usize::MAX),
+            dims => {
+                return Err(ConversionError::InvalidTensorData(format!(
+                    "sparse tensor '{}' values must be 1-D, got dims {dims:?}",
+                    values.name
+                )));
+            }
+        };
+        let numel = element_count_of(&sparse.dims)?;
+        let width = element_width(&dtype);
+        let source = element_bytes(&TensorData::from_tensor_proto(values)?, &dtype, nnz)?;
+        let indices = sparse_indices(sparse, &values.name, nnz, numel)?;
+
+        let mut dense = vec![0u8; numel * width];
+        for (i, &index) in indices.iter().enumerate() {
+            dense[index * width..(index + 1) * width]
+                .copy_from_slice(&source[i * width..(i + 1) * width]);
+        }
+        Ok(from_element_bytes(&dense, &dtype))
+    }
+
+    fn validate_count(
+        &self,
+        tensor: &TensorProto,
+        dtype: &DataType,
+        validation: CountValidation,
+    ) -> Result<()> {
+        let numel = element_count_of(&tensor.dims)?;
+        // Raw and packed payloads can only be measured in bytes
+        let (expected, actual, unit) = match self {
+            TensorData::Raw(v) => (dtype.storage_len(numel), v.len(), "bytes"),
+            data if dtype.bit_width() < 8 => {
+                (dtype.storage_len(numel), data.as_bytes().len(), "bytes")
+            }
+            data => (numel, data.count(), "elements"),
+        };
+
+        if actual == expected || (actual == 0 && validation == CountValidation::AllowEmpty) {
+            Ok(())
+        } else {
+            Err(ConversionError::InvalidTensorData(format!(
+                "tensor '{}' with dims {:?} needs {expected} {unit} of {dtype}, got {actual}",
+                tensor.name, tensor.dims
+            )))
+        }
+    }
+
+    fn read_payload(tensor: &TensorProto, dtype: &DataType) -> Result<Self> {
         if !tensor.raw_data.is_empty() {
             return Ok(TensorData::Raw(tensor.raw_data.clone()));
         }
@@ -91,69 +422,282 @@ impl TensorData {
                     Ok(TensorData::Uint8(vec![]))
                 }
             }
+            // Sub-byte types are kept packed, one byte per int32_data entry
+            DataType::Int4 => Ok(TensorData::Int4(PackedBits::from_int32_data(tensor, 4))),
+            DataType::Uint4 => Ok(TensorData::Uint4(PackedBits::from_int32_data(tensor, 4))),
+            DataType::Int2 => Ok(TensorData::Int2(PackedBits::from_int32_data(tensor, 2))),
+            DataType::Uint2 => Ok(TensorData::Uint2(PackedBits::from_int32_data(tensor, 2))),
+            DataType::Bool => {
+                if !tensor.int32_data.is_empty() {
+                    Ok(TensorData::Bool(
+                        tensor.int32_data.iter().map(|v| *v != 0).collect(),
+                    ))
+                } else {
+                    Ok(TensorData::Bool(vec![]))
+                }
+            }
+            DataType::Int16 => {
+                if !tensor.int32_data.is_empty() {
+                    Ok(TensorData::Int16(
+                        tensor.int32_data.iter().map(|v| *v as i16).collect(),
+                    ))
+                } else {
+                    Ok(TensorData::Int16(vec![]))
+                }
+            }
+            DataType::Uint16 => {
+                if !tensor.int32_data.is_empty() {
+                    Ok(TensorData::Uint16(
+                        tensor.int32_data.iter().map(|v| *v as u16).collect(),
+                    ))
+                } else {
+                    Ok(TensorData::Uint16(vec![]))
+                }
+            }
+            DataType::Float64 => {
+                if !tensor.double_data.is_empty() {
+                    Ok(TensorData::Float64(tensor.double_data.clone()))
+                } else {
+                    Ok(TensorData::Float64(vec![]))
+                }
+            }
+            DataType::BFloat16 => {
+                // BFloat16 data stored as int32_data in ONNX
+                if !tensor.int32_data.is_empty() {
+                    Ok(TensorData::BFloat16(
+                        tensor.int32_data.iter().map(|v| *v as u16).collect(),
+                    ))
+                } else {
+                    Ok(TensorData::BFloat16(vec![]))
+                }
+            }
+            DataType::Float8E4M3FN => Ok(TensorData::Float8E4M3FN(int32_as_bytes(tensor))),
+            DataType::Float8E4M3FNUZ => Ok(TensorData::Float8E4M3FNUZ(int32_as_bytes(tensor))),
+            DataType::Float8E5M2 => Ok(TensorData::Float8E5M2(int32_as_bytes(tensor))),
+            DataType::Float8E5M2FNUZ => Ok(TensorData::Float8E5M2FNUZ(int32_as_bytes(tensor))),
+            DataType::Float8E8M0 => Ok(TensorData::Float8E8M0(int32_as_bytes(tensor))),
+            DataType::Float4E2M1 => Ok(TensorData::Float4E2M1(PackedBits::from_int32_data(
+                tensor, 4,
+            ))),
+        }
+    }
+
+    /// Decodes little-endian `raw` bytes (the ONNX `raw_data` encoding) into
+    /// the typed variant for `dtype`, independent of host endianness.
+    ///
+    /// For 4-bit and 2-bit types every byte is assumed full; trim
+    /// `PackedBits::len` when the element count is odd.
+    pub fn decode(raw: &[u8], dtype: &DataType) -> Result<TensorData> {
+        let element_size = dtype.byte_size();
+        if dtype.bit_width() >= 8 && !raw.len().is_multiple_of(element_size) {
+            return Err(ConversionError::InvalidTensorData(format!(
+                "raw data length {} is not a multiple of the {dtype} element size {element_size}",
+                raw.len()
+            )));
+        }
+
+        let packed = |bit_width: usize| PackedBits {
+            bytes: raw.to_vec(),
+            len: raw.len() * (8 / bit_width),
+        };
+        Ok(match dtype {
+            DataType::Float32 => TensorData::Float32(decode_le(raw, f32::from_le_bytes)),
+            DataType::Float16 => TensorData::Float16(decode_le(raw, u16::from_le_bytes)),
+            DataType::Int64 => TensorData::Int64(decode_le(raw, i64::from_le_bytes)),
+            DataType::Int32 => TensorData::Int32(decode_le(raw, i32::from_le_bytes)),
+            DataType::Int8 => TensorData::Int8(raw.iter().map(|&b| b as i8).collect()),
+            DataType::Uint64 => TensorData::Uint64(decode_le(raw, u64::from_le_bytes)),
+            DataType::Uint32 => TensorData::Uint32(decode_le(raw, u32::from_le_bytes)),
+            DataType::Uint8 => TensorData::Uint8(raw.to_vec()),
+            DataType::Bool => TensorData::Bool(raw.iter().map(|&b| b != 0).collect()),
+            DataType::Int16 => TensorData::Int16(decode_le(raw, i16::from_le_bytes)),
+            DataType::Uint16 => TensorData::Uint16(decode_le(raw, u16::from_le_bytes)),
+            DataType::Float64 => TensorData::Float64(decode_le(raw, f64::from_le_bytes)),
+            DataType::BFloat16 => TensorData::BFloat16(decode_le(raw, u16::from_le_bytes)),
+            DataType::Float8E4M3FN => TensorData::Float8E4M3FN(raw.to_vec()),
+            DataType::Float8E4M3FNUZ => TensorData::Float8E4M3FNUZ(raw.to_vec()),
+            DataType::Float8E5M2 => TensorData::Float8E5M2(raw.to_vec()),
+            DataType::Float8E5M2FNUZ => TensorData::Float8E5M2FNUZ(raw.to_vec()),
+            DataType::Float8E8M0 => TensorData::Float8E8M0(raw.to_vec()),
+            DataType::Float4E2M1 => TensorData::Float4E2M1(packed(4)),
+            DataType::Int4 => TensorData::Int4(packed(4)),
+            DataType::Uint4 => TensorData::Uint4(packed(4)),
+            DataType::Int2 => TensorData::Int2(packed(2)),
+            DataType::Uint2 => TensorData::Uint2(packed(2)),
+        })
+    }
+
+    /// Returns the payload as the typed variant for `dtype`, decoding `Raw`
+    /// bytes. Typed payloads must already match `dtype`.
+    pub fn to_typed(&self, dtype: &DataType) -> Result<TensorData> {
+        match self {
+            TensorData::Raw(raw) => Self::decode(raw, dtype),
+            typed if typed.data_type().as_ref() == Some(dtype) => Ok(typed.clone()),
+            typed => Err(ConversionError::InvalidTensorData(format!(
+                "payload is {:?}, expected {dtype}",
+                typed.data_type()
+            ))),
         }
     }
 
-    pub fn to_tensor_proto(&self, name: String, dtype: DataType, shape: Vec<i64>) -> TensorProto {
-        TensorProto {
+    /// Builds a `TensorProto` with the payload in `raw_data`.
+    ///
+    /// Fails if the payload is not `dtype` or does not hold exactly the
+    /// number of elements `shape` describes.
+    pub fn to_tensor_proto(
+        &self,
+        name: String,
+        dtype: DataType,
+        shape: Vec<i64>,
+    ) -> Result<TensorProto> {
+        self.check_shape(&name, &dtype, &shape)?;
+        Ok(TensorProto {
             name,
             data_type: crate::data_types::webnn_to_onnx(dtype) as i32,
             dims: shape,
-            raw_data: self.as_bytes().to_vec(),
+            raw_data: self.as_bytes().into_owned(),
             ..Default::default()
+        })
+    }
+
+    /// Like [`TensorData::to_tensor_proto`], but moves byte-sized payloads
+    /// into `raw_data` instead of copying them.
+    pub fn into_tensor_proto(
+        self,
+        name: String,
+        dtype: DataType,
+        shape: Vec<i64>,
+    ) -> Result<TensorProto> {
+        self.check_shape(&name, &dtype, &shape)?;
+        Ok(TensorProto {
+            name,
+            data_type: crate::data_types::webnn_to_onnx(dtype) as i32,
+            dims: shape,
+            raw_data: self.into_bytes(),
+            ..Default::default()
+        })
+    }
+
+    /// Checks that the payload is a valid `dtype` tensor of `shape`.
+    pub(crate) fn check_shape(&self, name: &str, dtype: &DataType, shape: &[i64]) -> Result<()> {
+        let numel = element_count_of(shape)?;
+        // Raw bytes can only be measured in bytes
+        let (expected, actual, unit) = match self {
+            TensorData::Raw(v) => (dtype.storage_len(numel), v.len(), "bytes"),
+            typed => (numel, typed.element_count(dtype)?, "elements"),
+        };
+        if expected == actual {
+            Ok(())
+        } else {
+            Err(ConversionError::InvalidTensorData(format!(
+                "tensor '{name}' with dims {shape:?} needs {expected} {unit} of {dtype}, got {actual}"
+            )))
         }
     }
 
-    pub fn as_bytes(&self) -> &[u8] {
+    /// Little-endian bytes of the payload, as stored in ONNX `raw_data`.
+    ///
+    /// Byte-sized and packed payloads are borrowed; wider types are encoded
+    /// into a new buffer so the result is the same on every host.
+    pub fn as_bytes(&self) -> Cow<'_, [u8]> {
         match self {
-            TensorData::Raw(v) => v,
-            TensorData::Uint8(v) => v,
-            TensorData::Float32(v) => unsafe {
-                std::slice::from_raw_parts(
-                    v.as_ptr() as *const u8,
-                    v.len() * std::mem::size_of::<f32>(),
-                )
-            },
-            TensorData::Float16(v) => unsafe {
-                std::slice::from_raw_parts(
-                    v.as_ptr() as *const u8,
-                    v.len() * std::mem::size_of::<u16>(),
-                )
-            },
-            TensorData::Int64(v) => unsafe {
-                std::slice::from_raw_parts(
-                    v.as_ptr() as *const u8,
-                    v.len() * std::mem::size_of::<i64>(),
-                )
-            },
-            TensorData::Int32(v) => unsafe {
-                std::slice::from_raw_parts(
-                    v.as_ptr() as *const u8,
-                    v.len() * std::mem::size_of::<i32>(),
-                )
-            },
-            TensorData::Int8(v) => unsafe {
-                std::slice::from_raw_parts(
-                    v.as_ptr() as *const u8,
-                    v.len() * std::mem::size_of::<i8>(),
-                )
-            },
-            TensorData::Uint64(v) => unsafe {
-                std::slice::from_raw_parts(
-                    v.as_ptr() as *const u8,
-                    v.len() * std::mem::size_of::<u64>(),
-                )
-            },
-            TensorData::Uint32(v) => unsafe {
-                std::slice::from_raw_parts(
-                    v.as_ptr() as *const u8,
-                    v.len() * std::mem::size_of::<u32>(),
-                )
-            },
+            TensorData::Raw(v) | TensorData::Uint8(v) => Cow::Borrowed(v),
+            TensorData::Float32(v) => Cow::Owned(encode_le(v, f32::to_le_bytes)),
+            TensorData::Float16(v) => Cow::Owned(encode_le(v, u16::to_le_bytes)),
+            TensorData::Int64(v) => Cow::Owned(encode_le(v, i64::to_le_bytes)),
+            TensorData::Int32(v) => Cow::Owned(encode_le(v, i32::to_le_bytes)),
+            TensorData::Int8(v) => Cow::Owned(v.iter().map(|&x| x as u8).collect()),
+            TensorData::Uint64(v) => Cow::Owned(encode_le(v, u64::to_le_bytes)),
+            TensorData::Uint32(v) => Cow::Owned(encode_le(v, u32::to_le_bytes)),
+            TensorData::Bool(v) => Cow::Owned(v.iter().map(|&x| x as u8).collect()),
+            TensorData::Int16(v) => Cow::Owned(encode_le(v, i16::to_le_bytes)),
+            TensorData::Uint16(v) => Cow::Owned(encode_le(v, u16::to_le_bytes)),
+            TensorData::Float64(v) => Cow::Owned(encode_le(v, f64::to_le_bytes)),
+            TensorData::BFloat16(v) => Cow::Owned(encode_le(v, u16::to_le_bytes)),
+            TensorData::Float8E4M3FN(v)
+            | TensorData::Float8E4M3FNUZ(v)
+            | TensorData::Float8E5M2(v)
+            | TensorData::Float8E5M2FNUZ(v)
+            | TensorData::Float8E8M0(v) => Cow::Borrowed(v),
+            TensorData::Float4E2M1(p)
+            | TensorData::Int4(p)
+            | TensorData::Uint4(p)
+            | TensorData::Int2(p)
+            | TensorData::Uint2(p) => Cow::Borrowed(&p.bytes),
         }
     }
 
+    /// Consumes the payload and returns its little-endian bytes, reusing the
+    /// buffer when no conversion is needed.
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            TensorData::Raw(v)
+            | TensorData::Uint8(v)
+            | TensorData::Float8E4M3FN(v)
+            | TensorData::Float8E4M3FNUZ(v)
+            | TensorData::Float8E5M2(v)
+            | TensorData::Float8E5M2FNUZ(v)
+            | TensorData::Float8E8M0(v) => v,
+            TensorData::Float4E2M1(p)
+            | TensorData::Int4(p)
+            | TensorData::Uint4(p)
+            | TensorData::Int2(p)
+            | TensorData::Uint2(p) => p.bytes,
+            other => other.as_bytes().into_owned(),
+        }
+    }
+
+    /// Number of elements as `dtype`.
+    ///
+    /// `Raw` bytes must hold a whole number of elements; for 4-bit and 2-bit
+    /// types every byte is assumed full. Typed payloads must be `dtype`.
+    pub fn element_count(&self, dtype: &DataType) -> Result<usize> {
+        match self {
+            TensorData::Raw(raw) if dtype.bit_width() < 8 => {
+                Ok(raw.len() * (8 / dtype.bit_width()))
+            }
+            TensorData::Raw(raw) if raw.len().is_multiple_of(dtype.byte_size()) => {
+                Ok(raw.len() / dtype.byte_size())
+            }
+            TensorData::Raw(raw) => Err(ConversionError::InvalidTensorData(format!(
+                "raw data length {} is not a multiple of the {dtype} element size {}",
+                raw.len(),
+                dtype.byte_size()
+            ))),
+            typed if typed.data_type().as_ref() == Some(dtype) => Ok(typed.count()),
+            typed => Err(ConversionError::InvalidTensorData(format!(
+                "payload is {:?}, expected {dtype}",
+                typed.data_type()
+            ))),
+        }
+    }
+
+    /// Size of the payload in bytes as stored in `raw_data`, computed without
+    /// encoding it.
+    pub fn byte_len(&self) -> usize {
+        match self {
+            TensorData::Raw(v) => v.len(),
+            TensorData::Float4E2M1(p)
+            | TensorData::Int4(p)
+            | TensorData::Uint4(p)
+            | TensorData::Int2(p)
+            | TensorData::Uint2(p) => p.bytes.len(),
+            typed => {
+                let dtype = typed.data_type().expect("typed payload");
+                dtype.storage_len(typed.count())
+            }
+        }
+    }
+
+    #[deprecated(
+        note = "counts bytes for Raw but elements otherwise; use element_count or byte_len"
+    )]
     pub fn len(&self) -> usize {
+        self.count()
+    }
+
+    /// Element count of typed payloads, byte count of `Raw`.
+    pub(crate) fn count(&self) -> usize {
         match self {
             TensorData::Raw(v) => v.len(),
             TensorData::Float32(v) => v.len(),
@@ -164,11 +708,356 @@ impl TensorData {
             TensorData::Uint64(v) => v.len(),
             TensorData::Uint32(v) => v.len(),
             TensorData::Uint8(v) => v.len(),
+            TensorData::Bool(v) => v.len(),
+            TensorData::Int16(v) => v.len(),
+            TensorData::Uint16(v) => v.len(),
+            TensorData::Float64(v) => v.len(),
+            TensorData::BFloat16(v) => v.len(),
+            TensorData::Float8E4M3FN(v)
+            | TensorData::Float8E4M3FNUZ(v)
+            | TensorData::Float8E5M2(v)
+            | TensorData::Float8E5M2FNUZ(v)
+            | TensorData::Float8E8M0(v) => v.len(),
+            TensorData::Float4E2M1(p)
+            | TensorData::Int4(p)
+            | TensorData::Uint4(p)
+            | TensorData::Int2(p)
+            | TensorData::Uint2(p) => p.len,
         }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.len() == 0
+        self.count() == 0
+    }
+
+    /// Element type of the payload, or `None` for undecoded `Raw` bytes.
+    pub fn data_type(&self) -> Option<DataType> {
+        match self {
+            TensorData::Raw(_) => None,
+            TensorData::Float32(_) => Some(DataType::Float32),
+            TensorData::Float16(_) => Some(DataType::Float16),
+            TensorData::Int64(_) => Some(DataType::Int64),
+            TensorData::Int32(_) => Some(DataType::Int32),
+            TensorData::Int8(_) => Some(DataType::Int8),
+            TensorData::Uint64(_) => Some(DataType::Uint64),
+            TensorData::Uint32(_) => Some(DataType::Uint32),
+            TensorData::Uint8(_) => Some(DataType::Uint8),
+            TensorData::Bool(_) => Some(DataType::Bool),
+            TensorData::Int16(_) => Some(DataType::Int16),
+            TensorData::Uint16(_) => Some(DataType::Uint16),
+            TensorData::Float64(_) => Some(DataType::Float64),
+            TensorData::BFloat16(_) => Some(DataType::BFloat16),
+            TensorData::Float8E4M3FN(_) => Some(DataType::Float8E4M3FN),
+            TensorData::Float8E4M3FNUZ(_) => Some(DataType::Float8E4M3FNUZ),
+            TensorData::Float8E5M2(_) => Some(DataType::Float8E5M2),
+            TensorData::Float8E5M2FNUZ(_) => Some(DataType::Float8E5M2FNUZ),
+            TensorData::Float8E8M0(_) => Some(DataType::Float8E8M0),
+            TensorData::Float4E2M1(_) => Some(DataType::Float4E2M1),
+            TensorData::Int4(_) => Some(DataType::Int4),
+            TensorData::Uint4(_) => Some(DataType::Uint4),
+            TensorData::Int2(_) => Some(DataType::Int2),
+            TensorData::Uint2(_) => Some(DataType::Uint2),
+        }
+    }
+
+    /// Borrows the payload as `&[T]` if it is the variant for `T`.
+    pub fn as_slice<T: TensorElement>(&self) -> Option<&[T]> {
+        T::slice(self)
+    }
+
+    /// Element `index`, decoding half floats, 8-bit floats and packed values.
+    /// Returns `None` past the end and for undecoded `Raw` bytes.
+    pub fn get(&self, index: usize) -> Option<Scalar> {
+        let float = |v: f32| Some(Scalar::Float(v as f64));
+        let mini = |format, code: Option<&u8>| float(minifloat::decode(format, *code?));
+        let signed = |code: u8, bit_width: usize| {
+            let shift = 8 - bit_width;
+            Scalar::Int((((code << shift) as i8) >> shift) as i64)
+        };
+        match self {
+            TensorData::Raw(_) => None,
+            TensorData::Float32(v) => float(*v.get(index)?),
+            TensorData::Float64(v) => Some(Scalar::Float(*v.get(index)?)),
+            TensorData::Float16(v) => float(half::f16::from_bits(*v.get(index)?).to_f32()),
+            TensorData::BFloat16(v) => float(half::bf16::from_bits(*v.get(index)?).to_f32()),
+            TensorData::Float8E4M3FN(v) => mini(MiniFloatFormat::E4M3FN, v.get(index)),
+            TensorData::Float8E4M3FNUZ(v) => mini(MiniFloatFormat::E4M3FNUZ, v.get(index)),
+            TensorData::Float8E5M2(v) => mini(MiniFloatFormat::E5M2, v.get(index)),
+            TensorData::Float8E5M2FNUZ(v) => mini(MiniFloatFormat::E5M2FNUZ, v.get(index)),
+            TensorData::Float8E8M0(v) => mini(MiniFloatFormat::E8M0, v.get(index)),
+            TensorData::Float4E2M1(p) => mini(MiniFloatFormat::E2M1, p.get(index, 4).as_ref()),
+            TensorData::Int64(v) => Some(Scalar::Int(*v.get(index)?)),
+            TensorData::Int32(v) => Some(Scalar::Int(*v.get(index)? as i64)),
+            TensorData::Int16(v) => Some(Scalar::Int(*v.get(index)? as i64)),
+            TensorData::Int8(v) => Some(Scalar::Int(*v.get(index)? as i64)),
+            TensorData::Int4(p) => Some(signed(p.get(index, 4)?, 4)),
+            TensorData::Int2(p) => Some(signed(p.get(index, 2)?, 2)),
+            TensorData::Uint64(v) => Some(Scalar::Uint(*v.get(index)?)),
+            TensorData::Uint32(v) => Some(Scalar::Uint(*v.get(index)? as u64)),
+            TensorData::Uint16(v) => Some(Scalar::Uint(*v.get(index)? as u64)),
+            TensorData::Uint8(v) => Some(Scalar::Uint(*v.get(index)? as u64)),
+            TensorData::Uint4(p) => Some(Scalar::Uint(p.get(index, 4)? as u64)),
+            TensorData::Uint2(p) => Some(Scalar::Uint(p.get(index, 2)? as u64)),
+            TensorData::Bool(v) => Some(Scalar::Bool(*v.get(index)?)),
+        }
+    }
+
+    /// Iterates over the elements as `f64`, whatever the variant.
+    ///
+    /// `Raw` bytes are decoded as `dtype`, which is required for them. For
+    /// typed payloads `dtype` is optional but must match if given.
+    pub fn iter_f64<'a>(
+        &'a self,
+        dtype: Option<&DataType>,
+    ) -> Result<Box<dyn Iterator<Item = f64> + 'a>> {
+        match (self, dtype) {
+            (TensorData::Raw(raw), Some(dtype)) => {
+                let decoded = Self::decode(raw, dtype)?;
+                let values: Vec<f64> = decoded.iter_f64(None)?.collect();
+                Ok(Box::new(values.into_iter()))
+            }
+            (TensorData::Raw(_), None) => Err(ConversionError::InvalidTensorData(
+                "raw tensor data needs a data type to be read".to_string(),
+            )),
+            (typed, Some(dtype)) if typed.data_type().as_ref() != Some(dtype) => {
+                Err(ConversionError::InvalidTensorData(format!(
+                    "payload is {:?}, expected {dtype}",
+                    typed.data_type()
+                )))
+            }
+            (TensorData::Float32(v), _) => Ok(Box::new(v.iter().map(|&x| x as f64))),
+            (TensorData::Float64(v), _) => Ok(Box::new(v.iter().copied())),
+            (TensorData::Int64(v), _) => Ok(Box::new(v.iter().map(|&x| x as f64))),
+            (TensorData::Int32(v), _) => Ok(Box::new(v.iter().map(|&x| x as f64))),
+            (TensorData::Uint64(v), _) => Ok(Box::new(v.iter().map(|&x| x as f64))),
+            (TensorData::Uint8(v), _) => Ok(Box::new(v.iter().map(|&x| x as f64))),
+            (typed, _) => Ok(Box::new(
+                (0..typed.count()).map(|i| typed.get(i).expect("index < len").as_f64()),
+            )),
+        }
+    }
+
+    /// Copies the elements into `Vec<f32>`, converting other variants
+    /// (`Float16`/`BFloat16` in bulk, see [`HalfFormat::to_f32_vec`]).
+    /// `Raw` bytes are rejected; decode them first.
+    pub fn to_vec_f32(&self) -> Result<Vec<f32>> {
+        match self {
+            TensorData::Float32(v) => Ok(v.clone()),
+            TensorData::Float16(v) => Ok(HalfFormat::F16.to_f32_vec(v)),
+            TensorData::BFloat16(v) => Ok(HalfFormat::BF16.to_f32_vec(v)),
+            other => Ok(other.iter_f64(None)?.map(|x| x as f32).collect()),
+        }
+    }
+
+    /// Copies integer or boolean elements into `Vec<i64>`, the form shape
+    /// tensors such as Reshape's second input are usually needed in.
+    ///
+    /// Floats are rejected, and `Uint64` values above `i64::MAX` fail with
+    /// [`ConversionError::ValueOutOfRange`].
+    pub fn to_vec_i64(&self) -> Result<Vec<i64>> {
+        match self {
+            TensorData::Int64(v) => Ok(v.clone()),
+            TensorData::Raw(_) => Err(ConversionError::InvalidTensorData(
+                "raw tensor data needs a data type to be read".to_string(),
+            )),
+            other if other.data_type().is_some_and(|dt| dt.is_floating()) => {
+                Err(ConversionError::UnsupportedDataType(format!(
+                    "to_vec_i64 expects integer data, got {:?}",
+                    other.data_type()
+                )))
+            }
+            other => (0..other.count())
+                .map(|i| {
+                    let value = other.get(i).expect("index < len");
+                    value
+                        .as_i64()
+                        .ok_or_else(|| out_of_range(i, value.as_f64(), "Int64"))
+                })
+                .collect(),
+        }
+    }
+
+    /// Expands `Int4`/`Int2` to `Int8` and `Uint4`/`Uint2` to `Uint8`.
+    pub fn unpack_sub_byte(&self) -> Result<TensorData> {
+        match self {
+            TensorData::Int4(p) => Ok(TensorData::Int8(p.unpack_signed(4))),
+            TensorData::Uint4(p) => Ok(TensorData::Uint8(p.unpack(4))),
+            TensorData::Int2(p) => Ok(TensorData::Int8(p.unpack_signed(2))),
+            TensorData::Uint2(p) => Ok(TensorData::Uint8(p.unpack(2))),
+            other => Err(ConversionError::UnsupportedDataType(format!(
+                "unpack_sub_byte expects a packed integer payload, got {:?}",
+                other.data_type()
+            ))),
+        }
+    }
+
+    /// Packs `Int8` into `Int4`/`Int2` or `Uint8` into `Uint4`/`Uint2`, failing
+    /// with [`ConversionError::ValueOutOfRange`] if a value does not fit.
+    pub fn pack_sub_byte(&self, to: &DataType) -> Result<TensorData> {
+        let check = |i: usize, v: i64| {
+            if (v as f64) < to.min_value() || (v as f64) > to.max_value() {
+                Err(out_of_range(i, v, to.name()))
+            } else {
+                Ok(v as u8)
+            }
+        };
+        let codes: Vec<u8> = match (self, to) {
+            (TensorData::Int8(v), DataType::Int4 | DataType::Int2) => v
+                .iter()
+                .enumerate()
+                .map(|(i, &x)| check(i, x as i64))
+                .collect::<Result<_>>()?,
+            (TensorData::Uint8(v), DataType::Uint4 | DataType::Uint2) => v
+                .iter()
+                .enumerate()
+                .map(|(i, &x)| check(i, x as i64))
+                .collect::<Result<_>>()?,
+            (other, to) => {
+                return Err(ConversionError::UnsupportedDataType(format!(
+                    "cannot pack {:?} into {to}",
+                    other.data_type()
+                )));
+            }
+        };
+        let packed = PackedBits::pack(&codes, to.bit_width());
+        Ok(match to {
+            DataType::Int4 => TensorData::Int4(packed),
+            DataType::Uint4 => TensorData::Uint4(packed),
+            DataType::Int2 => TensorData::Int2(packed),
+            _ => TensorData::Uint2(packed),
+        })
+    }
+
+    /// Decodes 8-bit and 4-bit float payloads to `Float16` or `Float32`.
+    ///
+    /// Decoding is exact; NaN codes become NaN and `E5M2` infinities stay
+    /// infinite. Going to `Float16`, values of `Float8E8M0` beyond its range
+    /// become infinity and those below it flush towards zero.
+    pub fn upcast_float(&self, to: &DataType) -> Result<TensorData> {
+        let (format, codes) = match self {
+            TensorData::Float8E4M3FN(v) => (MiniFloatFormat::E4M3FN, v.clone()),
+            TensorData::Float8E4M3FNUZ(v) => (MiniFloatFormat::E4M3FNUZ, v.clone()),
+            TensorData::Float8E5M2(v) => (MiniFloatFormat::E5M2, v.clone()),
+            TensorData::Float8E5M2FNUZ(v) => (MiniFloatFormat::E5M2FNUZ, v.clone()),
+            TensorData::Float8E8M0(v) => (MiniFloatFormat::E8M0, v.clone()),
+            TensorData::Float4E2M1(p) => (MiniFloatFormat::E2M1, p.unpack(4)),
+            other => {
+                return Err(ConversionError::UnsupportedDataType(format!(
+                    "upcast_float expects an 8-bit or 4-bit float payload, got {:?}",
+                    other.data_type()
+                )));
+            }
+        };
+        let values = codes.iter().map(|&c| minifloat::decode(format, c));
+        match to {
+            DataType::Float32 => Ok(TensorData::Float32(values.collect())),
+            DataType::Float16 => Ok(TensorData::Float16(
+                values.map(|v| half::f16::from_f32(v).to_bits()).collect(),
+            )),
+            other => Err(ConversionError::UnsupportedDataType(format!(
+                "cannot upcast to {other}"
+            ))),
+        }
+    }
+
+    /// Converts the payload to the type `policy` lowers it to.
+    pub fn lower(&self, policy: &TypeLoweringPolicy) -> Result<TensorData> {
+        let from = self.data_type().ok_or_else(|| {
+            ConversionError::InvalidTensorData("cannot lower undecoded raw tensor data".to_string())
+        })?;
+        self.narrow(&policy.lower(&from)?)
+    }
+
+    /// Converts the payload to `to` by following the
+    /// [`TypeLoweringPolicy::fallback`] chain of its element type.
+    ///
+    /// Narrowing steps fail with [`ConversionError::ValueOutOfRange`] instead of
+    /// truncating. NaN and infinities survive `Float64` -> `Float32`.
+    pub fn narrow(&self, to: &DataType) -> Result<TensorData> {
+        let mut current = self.clone();
+        while let Some(from) = current.data_type() {
+            if &from == to {
+                return Ok(current);
+            }
+            let next = TypeLoweringPolicy::fallback(&from).ok_or_else(|| {
+                ConversionError::UnsupportedDataType(format!("cannot narrow {from:?} to {to:?}"))
+            })?;
+            current = current.lower_step(next)?;
+        }
+        Err(ConversionError::InvalidTensorData(
+            "cannot narrow undecoded raw tensor data".to_string(),
+        ))
+    }
+
+    fn lower_step(self, to: DataType) -> Result<TensorData> {
+        match (self, to) {
+            (TensorData::Int64(v), DataType::Int32) => Ok(TensorData::Int32(
+                v.iter()
+                    .enumerate()
+                    .map(|(i, &x)| i32::try_from(x).map_err(|_| out_of_range(i, x, "Int32")))
+                    .collect::<Result<_>>()?,
+            )),
+            (TensorData::Uint64(v), DataType::Uint32) => Ok(TensorData::Uint32(
+                v.iter()
+                    .enumerate()
+                    .map(|(i, &x)| u32::try_from(x).map_err(|_| out_of_range(i, x, "Uint32")))
+                    .collect::<Result<_>>()?,
+            )),
+            (TensorData::Float64(v), DataType::Float32) => Ok(TensorData::Float32(
+                v.iter()
+                    .enumerate()
+                    .map(|(i, &x)| {
+                        if x.is_finite() && x.abs() > f32::MAX as f64 {
+                            Err(out_of_range(i, x, "Float32"))
+                        } else {
+                            Ok(x as f32)
+                        }
+                    })
+                    .collect::<Result<_>>()?,
+            )),
+            (TensorData::Int16(v), DataType::Int32) => {
+                Ok(TensorData::Int32(v.iter().map(|&x| x as i32).collect()))
+            }
+            (TensorData::Int8(v), DataType::Int32) => {
+                Ok(TensorData::Int32(v.iter().map(|&x| x as i32).collect()))
+            }
+            (TensorData::Uint8(v), DataType::Int32) => {
+                Ok(TensorData::Int32(v.iter().map(|&x| x as i32).collect()))
+            }
+            (TensorData::Uint16(v), DataType::Uint32) => {
+                Ok(TensorData::Uint32(v.iter().map(|&x| x as u32).collect()))
+            }
+            (TensorData::Float16(v), DataType::Float32) => Ok(TensorData::Float32(
+                v.iter()
+                    .map(|&b| half::f16::from_bits(b).to_f32())
+                    .collect(),
+            )),
+            (TensorData::BFloat16(v), DataType::Float32) => Ok(TensorData::Float32(
+                v.iter()
+                    .map(|&b| half::bf16::from_bits(b).to_f32())
+                    .collect(),
+            )),
+            (TensorData::Bool(v), DataType::Uint8) => {
+                Ok(TensorData::Uint8(v.iter().map(|&x| x as u8).collect()))
+            }
+            (data @ (TensorData::Int4(_) | TensorData::Uint4(_)), _) => data.unpack_sub_byte(),
+            (data @ (TensorData::Int2(_) | TensorData::Uint2(_)), to) => {
+                data.unpack_sub_byte()?.pack_sub_byte(&to)
+            }
+            (
+                data @ (TensorData::Float8E4M3FN(_)
+                | TensorData::Float8E4M3FNUZ(_)
+                | TensorData::Float8E5M2(_)
+                | TensorData::Float8E5M2FNUZ(_)
+                | TensorData::Float8E8M0(_)
+                | TensorData::Float4E2M1(_)),
+                to,
+            ) => data.upcast_float(&to),
+            (data, to) => Err(ConversionError::UnsupportedDataType(format!(
+                "cannot narrow {:?} to {to:?}",
+                data.data_type()
+            ))),
+        }
     }
 
     pub fn scalar(dtype: DataType, value: f32) -> Self {
@@ -181,6 +1070,20 @@ impl TensorData {
             DataType::Uint32 => TensorData::Uint32(vec![value as u32]),
             DataType::Uint64 => TensorData::Uint64(vec![value as u64]),
             DataType::Uint8 => TensorData::Uint8(vec![value as u8]),
+            DataType::Int4 | DataType::Uint4 | DataType::Int2 | DataType::Uint2 => {
+                Self::filled(dtype, &[1], value)
+            }
+            DataType::Bool => TensorData::Bool(vec![value != 0.0]),
+            DataType::Int16 => TensorData::Int16(vec![value as i16]),
+            DataType::Uint16 => TensorData::Uint16(vec![value as u16]),
+            DataType::Float64 => TensorData::Float64(vec![value as f64]),
+            DataType::BFloat16 => TensorData::BFloat16(vec![half::bf16::from_f32(value).to_bits()]),
+            DataType::Float8E4M3FN
+            | DataType::Float8E4M3FNUZ
+            | DataType::Float8E5M2
+            | DataType::Float8E5M2FNUZ
+            | DataType::Float8E8M0
+            | DataType::Float4E2M1 => Self::filled(dtype, &[1], value),
         }
     }
 
@@ -198,6 +1101,356 @@ impl TensorData {
             DataType::Uint32 => TensorData::Uint32(vec![value as u32; count]),
             DataType::Uint64 => TensorData::Uint64(vec![value as u64; count]),
             DataType::Uint8 => TensorData::Uint8(vec![value as u8; count]),
+            DataType::Int4 => {
+                TensorData::Int4(PackedBits::pack_signed(&vec![value as i8; count], 4))
+            }
+            DataType::Uint4 => TensorData::Uint4(PackedBits::pack(&vec![value as u8; count], 4)),
+            DataType::Int2 => {
+                TensorData::Int2(PackedBits::pack_signed(&vec![value as i8; count], 2))
+            }
+            DataType::Uint2 => TensorData::Uint2(PackedBits::pack(&vec![value as u8; count], 2)),
+            DataType::Bool => TensorData::Bool(vec![value != 0.0; count]),
+            DataType::Int16 => TensorData::Int16(vec![value as i16; count]),
+            DataType::Uint16 => TensorData::Uint16(vec![value as u16; count]),
+            DataType::Float64 => TensorData::Float64(vec![value as f64; count]),
+            DataType::BFloat16 => {
+                let bits = half::bf16::from_f32(value).to_bits();
+                TensorData::BFloat16(vec![bits; count])
+            }
+            DataType::Float8E4M3FN => TensorData::Float8E4M3FN(vec![
+                minifloat::encode(
+                    MiniFloatFormat::E4M3FN,
+                    value,
+                    true
+                );
+                count
+            ]),
+            DataType::Float8E4M3FNUZ => TensorData::Float8E4M3FNUZ(vec![
+                minifloat::encode(
+                    MiniFloatFormat::E4M3FNUZ,
+                    value,
+                    true
+                );
+                count
+            ]),
+            DataType::Float8E5M2 => {
+                TensorData::Float8E5M2(vec![
+                    minifloat::encode(MiniFloatFormat::E5M2, value, true);
+                    count
+                ])
+            }
+            DataType::Float8E5M2FNUZ => TensorData::Float8E5M2FNUZ(vec![
+                minifloat::encode(
+                    MiniFloatFormat::E5M2FNUZ,
+                    value,
+                    true
+                );
+                count
+            ]),
+            DataType::Float8E8M0 => {
+                TensorData::Float8E8M0(vec![
+                    minifloat::encode(MiniFloatFormat::E8M0, value, true);
+                    count
+                ])
+            }
+            DataType::Float4E2M1 => {
+                let code = minifloat::encode(MiniFloatFormat::E2M1, value, true);
+                TensorData::Float4E2M1(PackedBits::pack(&vec![code; count], 4))
+            }
+        }
+    }
+}
+
+/// How [`SparseTensorBuilder`] stores the indices of non-zero elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SparseIndexLayout {
+    /// One linearized index per element, shape `[NNZ]`.
+    #[default]
+    Linear,
+    /// One coordinate per dimension, shape `[NNZ, rank]`.
+    Coordinates,
+}
+
+/// Re-sparsifies dense tensors that are mostly zero.
+///
+/// An element counts as zero when all of its bits are zero, so `-0.0` is
+/// kept and densifying the result restores the input exactly.
+#[derive(Debug, Clone)]
+pub struct SparseTensorBuilder {
+    name: String,
+    dtype: DataType,
+    dims: Vec<i64>,
+    min_sparsity: f64,
+    index_layout: SparseIndexLayout,
+}
+
+impl SparseTensorBuilder {
+    /// Fraction of zero elements below which [`SparseTensorBuilder::build`]
+    /// keeps the tensor dense.
+    pub const DEFAULT_MIN_SPARSITY: f64 = 0.5;
+
+    pub fn new(name: impl Into<String>, dtype: DataType, dims: Vec<i64>) -> Self {
+        Self {
+            name: name.into(),
+            dtype,
+            dims,
+            min_sparsity: Self::DEFAULT_MIN_SPARSITY,
+            index_layout: SparseIndexLayout::default(),
+        }
+    }
+
+    pub fn min_sparsity(mut self, min_sparsity: f64) -> Self {
+        self.min_sparsity = min_sparsity;
+        self
+    }
+
+    pub fn index_layout(mut self, index_layout: SparseIndexLayout) -> Self {
+        self.index_layout = index_layout;
+        self
+    }
+
+    /// Returns `None` if fewer than `min_sparsity` of the elements are zero.
+    pub fn build(&self, data: &TensorData) -> Result<Option<SparseTensorProto>> {
+        let numel = element_count_of(&self.dims)?;
+        let width = element_width(&self.dtype);
+        let bytes = element_bytes(data, &self.dtype, numel)?;
+        if bytes.len() != numel * width {
+            return Err(ConversionError::InvalidTensorData(format!(
+                "tensor '{}' with dims {:?} needs {numel} elements of {}, got {}",
+                self.name,
+                self.dims,
+                self.dtype,
+                bytes.len() / width
+            )));
+        }
+
+        let nonzero: Vec<usize> = bytes
+            .chunks_exact(width)
+            .enumerate()
+            .filter(|(_, element)| element.iter().any(|&b| b != 0))
+            .map(|(i, _)| i)
+            .collect();
+        if numel == 0 || ((numel - nonzero.len()) as f64) < self.min_sparsity * numel as f64 {
+            return Ok(None);
+        }
+
+        let values: Vec<u8> = nonzero
+            .iter()
+            .flat_map(|&i| &bytes[i * width..(i + 1) * width])
+            .copied()
+            .collect();
+        let nnz = nonzero.len() as i64;
+        let (indices, index_dims) = match self.index_layout {
+            SparseIndexLayout::Linear => (nonzero.iter().map(|&i| i as i64).collect(), vec![nnz]),
+            SparseIndexLayout::Coordinates => {
+                let rank = self.dims.len();
+                let mut coords = vec![0i64; nonzero.len() * rank];
+                for (row, &index) in coords.chunks_exact_mut(rank.max(1)).zip(&nonzero) {
+                    let mut rest = index as i64;
+                    for (c, &d) in row.iter_mut().zip(&self.dims).rev() {
+                        *c = rest % d;
+                        rest /= d;
+                    }
+                }
+                (coords, vec![nnz, rank as i64])
+            }
+        };
+
+        Ok(Some(SparseTensorProto {
+            values: Some(from_element_bytes(&values, &self.dtype).into_tensor_proto(
+                self.name.clone(),
+                self.dtype.clone(),
+                vec![nnz],
+            )?),
+            indices: Some(TensorData::Int64(indices).into_tensor_proto(
+                String::new(),
+                DataType::Int64,
+                index_dims,
+            )?),
+            dims: self.dims.clone(),
+        }))
+    }
+}
+
+/// Reads the indices of `sparse` as linear offsets into the dense tensor.
+fn sparse_indices(
+    sparse: &SparseTensorProto,
+    name: &str,
+    nnz: usize,
+    numel: usize,
+) -> Result<Vec<usize>> {
+    let invalid = |message: String| {
+        ConversionError::InvalidTensorData(format!("sparse tensor '{name}' {message}"))
+    };
+    let Some(indices) = sparse.indices.as_ref() else {
+        return if nnz == 0 {
+            Ok(vec![])
+        } else {
+            Err(invalid("has values but no indices".to_string()))
+        };
+    };
+    let raw = match TensorData::from_tensor_proto(indices)?.to_typed(&DataType::Int64) {
+        Ok(TensorData::Int64(v)) => v,
+        _ => return Err(invalid("indices must be int64".to_string())),
+    };
+
+    let rank = sparse.dims.len();
+    let linear: Vec<i64> = match indices.dims.as_slice() {
+        [n] if *n as usize == nnz => raw,
+        [n, r] if *n as usize == nnz && *r as usize == rank => (0..nnz)
+            .map(|i| {
+                let coords = &raw[i * rank..(i + 1) * rank];
+                coords
+                    .iter()
+                    .zip(&sparse.dims)
+                    .try_fold(0i64, |acc, (&c, &d)| {
+                        if (0..d).contains(&c) {
+                            Ok(acc * d + c)
+                        } else {
+                            Err(invalid(format!(
+                                "coordinate {coords:?} is outside {:?}",
+                                sparse.dims
+                            )))
+                        }
+                    })
+            })
+            .collect::<Result<_>>()?,
+        dims => {
+            return Err(invalid(format!(
+                "indices dims {dims:?} must be [{nnz}] or [{nnz}, {rank}]"
+            )));
+        }
+    };
+
+    let mut previous = None;
+    linear
+        .into_iter()
+        .map(|index| {
+            let valid = usize::try_from(index).is_ok_and(|i| i < numel);
+            if !valid || previous.is_some_and(|p| p >= index) {
+                return Err(invalid(format!(
+                    "index {index} is out of range or not ascending"
+                )));
+            }
+            previous = Some(index);
+            Ok(index as usize)
+        })
+        .collect()
+}
+
+/// Bytes per element in [`element_bytes`]; packed types use one byte per code.
+pub(crate) fn element_width(dtype: &DataType) -> usize {
+    dtype.byte_size().max(1)
+}
+
+/// Little-endian bytes of `data` with a fixed width per element, unpacking
+/// sub-byte codes so elements can be addressed by index. `count` trims the
+/// padding code of packed `Raw` payloads with an odd element count.
+pub(crate) fn element_bytes(data: &TensorData, dtype: &DataType, count: usize) -> Result<Vec<u8>> {
+    Ok(match data.to_typed(dtype)? {
+        TensorData::Float4E2M1(mut p)
+        | TensorData::Int4(mut p)
+        | TensorData::Uint4(mut p)
+        | TensorData::Int2(mut p)
+        | TensorData::Uint2(mut p) => {
+            p.len = p.len.min(count);
+            p.unpack(dtype.bit_width())
+        }
+        typed => typed.into_bytes(),
+    })
+}
+
+pub(crate) fn from_element_bytes(bytes: &[u8], dtype: &DataType) -> TensorData {
+    let packed = || PackedBits::pack(bytes, dtype.bit_width());
+    match dtype {
+        DataType::Float4E2M1 => TensorData::Float4E2M1(packed()),
+        DataType::Int4 => TensorData::Int4(packed()),
+        DataType::Uint4 => TensorData::Uint4(packed()),
+        DataType::Int2 => TensorData::Int2(packed()),
+        DataType::Uint2 => TensorData::Uint2(packed()),
+        _ => TensorData::decode(bytes, dtype).expect("bytes hold whole elements"),
+    }
+}
+
+fn encode_le<T: Copy, const N: usize>(values: &[T], to_le: fn(T) -> [u8; N]) -> Vec<u8> {
+    values.iter().flat_map(|&v| to_le(v)).collect()
+}
+
+fn decode_le<T, const N: usize>(raw: &[u8], from_le: fn([u8; N]) -> T) -> Vec<T> {
+    raw.chunks_exact(N)
+        .map(|chunk| from_le(chunk.try_into().expect("chunk has N bytes")))
+        .collect()
+}
+
+fn int32_as_bytes(tensor: &TensorProto) -> Vec<u8> {
+    tensor.int32_data.iter().map(|v| *v as u8).collect()
+}
+
+fn out_of_range(index: usize, value: impl std::fmt::Display, target: &str) -> ConversionError {
+    ConversionError::ValueOutOfRange(format!(
+        "element {index} ({value}) does not fit in {target}"
+    ))
+}
+
+/// Borrowed view of a tensor stored in `TensorProto.raw_data`.
+///
+/// Reading weights through a view avoids cloning `raw_data`, which for large
+/// models would double peak memory. Decode only the tensors that are needed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TensorView<'a> {
+    pub name: &'a str,
+    pub dtype: DataType,
+    pub dims: &'a [i64],
+    pub bytes: &'a [u8],
+}
+
+impl<'a> TensorView<'a> {
+    pub fn new(name: &'a str, dtype: DataType, dims: &'a [i64], bytes: &'a [u8]) -> Result<Self> {
+        let expected = dtype.storage_len(element_count_of(dims)?);
+        if bytes.len() != expected {
+            return Err(ConversionError::InvalidTensorData(format!(
+                "tensor '{name}' with dims {dims:?} needs {expected} bytes of {dtype}, got {}",
+                bytes.len()
+            )));
+        }
+        Ok(Self {
+            name,
+            dtype,
+            dims,
+            bytes,
+        })
+    }
+
+    /// Views the `raw_data` of `tensor`. Tensors whose values live in the typed
+    /// fields (`float_data`, `int64_data`, ...) cannot be viewed; read them with
+    /// [`TensorData::from_tensor_proto`].
+    pub fn from_tensor_proto(tensor: &'a TensorProto) -> Result<Self> {
+        let dtype = crate::data_types::onnx_to_webnn(tensor.data_type)?;
+        if tensor.raw_data.is_empty() && element_count_of(&tensor.dims)? > 0 {
+            return Err(ConversionError::InvalidTensorData(format!(
+                "tensor '{}' has no raw_data to view",
+                tensor.name
+            )));
+        }
+        Self::new(&tensor.name, dtype, &tensor.dims, &tensor.raw_data)
+    }
+
+    pub fn element_count(&self) -> usize {
+        // dims were validated in new()
+        self.dims.iter().product::<i64>() as usize
+    }
+
+    /// Decodes the viewed bytes into an owned typed payload.
+    pub fn decode(&self) -> Result<TensorData> {
+        let mut data = TensorData::decode(self.bytes, &self.dtype)?;
+        if let TensorData::Float4E2M1(p)
+        | TensorData::Int4(p)
+        | TensorData::Uint4(p)
+        | TensorData::Int2(p)
+        | TensorData::Uint2(p) = &mut data
+        {
+            p.len = self.element_count();
         }
+        Ok(data)
     }
 }
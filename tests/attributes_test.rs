@@ -110,3 +110,20 @@ fn test_attr_parser_string() {
     assert_eq!(parser.get_string("activation"), Some("relu".to_string()));
     assert_eq!(parser.get_string("missing"), None);
 }
+
+#[test]
+fn test_attr_parser_tensor() {
+    let value = webnn_onnx_utils::protos::onnx::TensorProto {
+        name: "value".to_string(),
+        dims: vec![1],
+        ..Default::default()
+    };
+    let attrs = AttrBuilder::new()
+        .add_tensor("value", value.clone())
+        .add_int("axis", 0)
+        .build();
+    let parser = AttrParser::new(&attrs);
+
+    assert_eq!(parser.get_tensor("value"), Some(&value));
+    assert_eq!(parser.get_tensor("axis"), None);
+}
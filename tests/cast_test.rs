@@ -0,0 +1,232 @@
+use webnn_onnx_utils::ConversionError;
+use webnn_onnx_utils::cast::CastMode;
+use webnn_onnx_utils::data_types::DataType;
+use webnn_onnx_utils::tensor_data::{PackedBits, TensorData};
+
+#[test]
+fn test_cast_int64_to_int32_modes() {
+    let data = TensorData::Int64(vec![1, -1, i64::from(i32::MAX) + 1, i64::MIN]);
+
+    let saturated = data.cast(DataType::Int32, CastMode::Saturate).unwrap();
+    assert_eq!(saturated.out_of_range, 2);
+    match saturated.data {
+        TensorData::Int32(v) => assert_eq!(v, vec![1, -1, i32::MAX, i32::MIN]),
+        other => panic!("Expected Int32 variant, got {other:?}"),
+    }
+
+    let wrapped = data.cast(DataType::Int32, CastMode::Wrap).unwrap();
+    assert_eq!(wrapped.out_of_range, 2);
+    match wrapped.data {
+        TensorData::Int32(v) => assert_eq!(v, vec![1, -1, i32::MIN, 0]),
+        other => panic!("Expected Int32 variant, got {other:?}"),
+    }
+
+    match data.cast(DataType::Int32, CastMode::Checked) {
+        Err(ConversionError::ValueOutOfRange(msg)) => {
+            assert!(msg.starts_with("2 of 4 elements"), "{msg}")
+        }
+        other => panic!("Expected ValueOutOfRange, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_cast_float32_to_float16_rounds_to_nearest_even() {
+    // 2049 lies halfway between 2048 and 2050; 2051 between 2050 and 2052
+    let data = TensorData::Float32(vec![2049.0, 2051.0, f32::INFINITY, f32::NAN, 1e6]);
+    let out = data.cast(DataType::Float16, CastMode::Saturate).unwrap();
+    assert_eq!(out.out_of_range, 1);
+    match out.data {
+        TensorData::Float16(v) => {
+            let v: Vec<f32> = v
+                .iter()
+                .map(|&b| half::f16::from_bits(b).to_f32())
+                .collect();
+            assert_eq!(v[..3], [2048.0, 2052.0, f32::INFINITY]);
+            assert!(v[3].is_nan());
+            assert_eq!(v[4], 65504.0);
+        }
+        other => panic!("Expected Float16 variant, got {other:?}"),
+    }
+
+    match data.cast(DataType::Float16, CastMode::Wrap).unwrap().data {
+        TensorData::Float16(v) => assert!(half::f16::from_bits(v[4]).is_infinite()),
+        other => panic!("Expected Float16 variant, got {other:?}"),
+    }
+    assert!(data.cast(DataType::Float16, CastMode::Checked).is_err());
+}
+
+#[test]
+fn test_cast_float_to_int_truncates() {
+    let data = TensorData::Float32(vec![2.7, -2.7, 300.0, f32::NAN]);
+    let out = data.cast(DataType::Uint8, CastMode::Saturate).unwrap();
+    assert_eq!(out.out_of_range, 3);
+    match out.data {
+        TensorData::Uint8(v) => assert_eq!(v, vec![2, 0, 255, 0]),
+        other => panic!("Expected Uint8 variant, got {other:?}"),
+    }
+
+    match data.cast(DataType::Uint8, CastMode::Wrap).unwrap().data {
+        TensorData::Uint8(v) => assert_eq!(v, vec![2, 254, 44, 0]),
+        other => panic!("Expected Uint8 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_cast_packed_and_minifloat_types() {
+    let data = TensorData::Int32(vec![-9, -8, 7, 8]);
+    let out = data.cast(DataType::Int4, CastMode::Saturate).unwrap();
+    assert_eq!(out.out_of_range, 2);
+    match out.data {
+        TensorData::Int4(p) => assert_eq!(p.unpack_signed(4), vec![-8, -8, 7, 7]),
+        other => panic!("Expected Int4 variant, got {other:?}"),
+    }
+    match data.cast(DataType::Int4, CastMode::Wrap).unwrap().data {
+        TensorData::Int4(p) => assert_eq!(p.unpack_signed(4), vec![7, -8, 7, -8]),
+        other => panic!("Expected Int4 variant, got {other:?}"),
+    }
+
+    let floats = TensorData::Float32(vec![1.0, 500.0, f32::INFINITY]);
+    let out = floats
+        .cast(DataType::Float8E4M3FN, CastMode::Saturate)
+        .unwrap();
+    assert_eq!(out.out_of_range, 2);
+    match out.data {
+        TensorData::Float8E4M3FN(v) => assert_eq!(v, vec![0x38, 0x7E, 0x7E]),
+        other => panic!("Expected Float8E4M3FN variant, got {other:?}"),
+    }
+
+    let back = TensorData::Uint4(PackedBits::pack(&[0, 15], 4))
+        .cast(DataType::Float32, CastMode::Checked)
+        .unwrap();
+    match back.data {
+        TensorData::Float32(v) => assert_eq!(v, vec![0.0, 15.0]),
+        other => panic!("Expected Float32 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_cast_bool_and_wide_integers() {
+    let data = TensorData::Float64(vec![0.0, -0.5, f64::NAN]);
+    match data.cast(DataType::Bool, CastMode::Checked).unwrap().data {
+        TensorData::Bool(v) => assert_eq!(v, vec![false, true, true]),
+        other => panic!("Expected Bool variant, got {other:?}"),
+    }
+
+    let big = TensorData::Uint64(vec![u64::MAX]);
+    let out = big.cast(DataType::Int64, CastMode::Wrap).unwrap();
+    match out.data {
+        TensorData::Int64(v) => assert_eq!(v, vec![-1]),
+        other => panic!("Expected Int64 variant, got {other:?}"),
+    }
+    match big.cast(DataType::Uint64, CastMode::Checked).unwrap().data {
+        TensorData::Uint64(v) => assert_eq!(v, vec![u64::MAX]),
+        other => panic!("Expected Uint64 variant, got {other:?}"),
+    }
+
+    assert!(
+        TensorData::Raw(vec![0; 4])
+            .cast(DataType::Int32, CastMode::Checked)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_cast_checked_accepts_values_that_round_into_range() {
+    // 65519 rounds down to 65504, 65520 is the halfway point and rounds to infinity
+    let data = TensorData::Float32(vec![65519.0, -65519.0]);
+    match data
+        .cast(DataType::Float16, CastMode::Checked)
+        .unwrap()
+        .data
+    {
+        TensorData::Float16(v) => assert_eq!(v, vec![0x7bff, 0xfbff]),
+        other => panic!("Expected Float16 variant, got {other:?}"),
+    }
+    let data = TensorData::Float32(vec![65520.0]);
+    assert!(data.cast(DataType::Float16, CastMode::Checked).is_err());
+
+    // E4M3FN steps by 32 near its maximum 448; up to the halfway point 464
+    // rounds down to it, beyond rounds up to 480, which does not exist
+    let data = TensorData::Float32(vec![460.0, 464.0]);
+    match data
+        .cast(DataType::Float8E4M3FN, CastMode::Checked)
+        .unwrap()
+        .data
+    {
+        TensorData::Float8E4M3FN(v) => assert_eq!(v, vec![0x7E, 0x7E]),
+        other => panic!("Expected Float8E4M3FN variant, got {other:?}"),
+    }
+    assert!(
+        TensorData::Float32(vec![470.0])
+            .cast(DataType::Float8E4M3FN, CastMode::Checked)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_cast_float64_to_float8_rounds_once() {
+    // just above the halfway point between 1.0 and 1.125, but rounding through
+    // f32 first lands exactly on it and then ties down to 1.0
+    let x = 1.0625 + 2f64.powi(-40);
+    let out = TensorData::Float64(vec![x])
+        .cast(DataType::Float8E4M3FN, CastMode::Checked)
+        .unwrap();
+    match out.data {
+        TensorData::Float8E4M3FN(v) => assert_eq!(v, vec![0x39]),
+        other => panic!("Expected Float8E4M3FN variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_cast_large_integers_to_float_round_once() {
+    // not exact in f64, which would round to 2^60 + 2^36 and then tie down to 2^60
+    let x = (1i64 << 60) + (1 << 36) + 1;
+    match TensorData::Int64(vec![x])
+        .cast(DataType::Float32, CastMode::Checked)
+        .unwrap()
+        .data
+    {
+        TensorData::Float32(v) => assert_eq!(v, vec![x as f32]),
+        other => panic!("Expected Float32 variant, got {other:?}"),
+    }
+
+    // just above the halfway point between 2^60 and the next BFloat16
+    let x = (1i64 << 60) + (1 << 52) + 1;
+    match TensorData::Int64(vec![x])
+        .cast(DataType::BFloat16, CastMode::Checked)
+        .unwrap()
+        .data
+    {
+        TensorData::BFloat16(v) => assert_eq!(v, vec![0x5d81]),
+        other => panic!("Expected BFloat16 variant, got {other:?}"),
+    }
+    // the same from Float64, where the bit that breaks the tie is the last one
+    match TensorData::Float64(vec![2f64.powi(60) + 2f64.powi(52) + 2f64.powi(8)])
+        .cast(DataType::BFloat16, CastMode::Checked)
+        .unwrap()
+        .data
+    {
+        TensorData::BFloat16(v) => assert_eq!(v, vec![0x5d81]),
+        other => panic!("Expected BFloat16 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_cast_negative_to_float8e8m0() {
+    let data = TensorData::Float32(vec![-2.0, 2.0]);
+    let out = data.cast(DataType::Float8E8M0, CastMode::Wrap).unwrap();
+    assert_eq!(out.out_of_range, 1);
+    match out.data {
+        TensorData::Float8E8M0(v) => assert_eq!(v, vec![0xFF, 128]),
+        other => panic!("Expected Float8E8M0 variant, got {other:?}"),
+    }
+    match data
+        .cast(DataType::Float8E8M0, CastMode::Saturate)
+        .unwrap()
+        .data
+    {
+        TensorData::Float8E8M0(v) => assert_eq!(v, vec![0, 128]),
+        other => panic!("Expected Float8E8M0 variant, got {other:?}"),
+    }
+    assert!(data.cast(DataType::Float8E8M0, CastMode::Checked).is_err());
+}
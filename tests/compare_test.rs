@@ -0,0 +1,89 @@
+use webnn_onnx_utils::ConversionError;
+use webnn_onnx_utils::compare::{ComparisonReport, Tolerance};
+use webnn_onnx_utils::tensor_data::TensorData;
+
+#[test]
+fn test_compare_epsilon() {
+    let actual = TensorData::Float32(vec![1.0, 2.05, 3.0, -0.0, 100.5]);
+    let expected = TensorData::Float32(vec![1.0, 2.0, 3.0, 0.0, 100.0]);
+
+    let report = actual.compare(&expected, Tolerance::Exact).unwrap();
+    assert_eq!(report.mismatch_count, 2);
+    assert_eq!(report.first_mismatch_index, Some(1));
+    assert_eq!(report.max_error, 0.5);
+    assert_eq!(report.max_error_index, Some(4));
+
+    let absolute = Tolerance::Epsilon {
+        absolute: 0.1,
+        relative: 0.0,
+    };
+    let report = actual.compare(&expected, absolute).unwrap();
+    assert_eq!(report.mismatch_count, 1);
+    assert_eq!(report.first_mismatch_index, Some(4));
+
+    let relative = Tolerance::Epsilon {
+        absolute: 0.0,
+        relative: 0.03,
+    };
+    assert!(actual.compare(&expected, relative).unwrap().passed());
+}
+
+#[test]
+fn test_compare_ulp() {
+    let one_up = f32::from_bits(1.0f32.to_bits() + 1);
+    let actual = TensorData::Float32(vec![one_up, f32::from_bits(1), 0.0]);
+    let expected = TensorData::Float32(vec![1.0, -f32::from_bits(1), -0.0]);
+    let report = actual.compare(&expected, Tolerance::Ulp(1)).unwrap();
+    // the smallest subnormals on either side of zero are 2 ULPs apart
+    assert_eq!(report.mismatch_count, 1);
+    assert_eq!(report.max_error, 2.0);
+    assert_eq!(report.max_error_index, Some(1));
+    assert!(
+        actual
+            .compare(&expected, Tolerance::Ulp(2))
+            .unwrap()
+            .passed()
+    );
+
+    // Float16 ULPs are counted on the 16-bit patterns
+    let actual = TensorData::Float16(vec![0x3c00, 0x3c03, 0x7bff]);
+    let expected = TensorData::Float16(vec![0x3c01, 0x3c00, 0x7c00]);
+    let report = actual.compare(&expected, Tolerance::Ulp(1)).unwrap();
+    assert_eq!(report.mismatch_count, 1);
+    assert_eq!(report.max_error, 3.0);
+    assert_eq!(report.first_mismatch_index, Some(1));
+}
+
+#[test]
+fn test_compare_nan_and_integers() {
+    let actual = TensorData::Float32(vec![f32::NAN, f32::NAN, 1.0, f32::INFINITY]);
+    let expected = TensorData::Float32(vec![f32::NAN, 0.0, f32::NAN, f32::INFINITY]);
+    let loose = Tolerance::Epsilon {
+        absolute: 1e9,
+        relative: 1.0,
+    };
+    let report = actual.compare(&expected, loose).unwrap();
+    assert_eq!(
+        report,
+        ComparisonReport {
+            count: 4,
+            mismatch_count: 2,
+            nan_mismatch_count: 2,
+            max_error: 0.0,
+            max_error_index: None,
+            first_mismatch_index: Some(1),
+        }
+    );
+
+    // integers are exact even beyond f64 precision, whatever the tolerance
+    let actual = TensorData::Int64(vec![1 << 60, 5]);
+    let expected = TensorData::Int64(vec![(1 << 60) + 1, 5]);
+    let report = actual.compare(&expected, Tolerance::Ulp(10)).unwrap();
+    assert_eq!(report.mismatch_count, 1);
+    assert_eq!(report.first_mismatch_index, Some(0));
+
+    let result = actual.compare(&TensorData::Int32(vec![1, 5]), Tolerance::Exact);
+    assert!(matches!(result, Err(ConversionError::InvalidTensorData(_))));
+    let result = actual.compare(&TensorData::Int64(vec![1]), Tolerance::Exact);
+    assert!(matches!(result, Err(ConversionError::InvalidTensorData(_))));
+}
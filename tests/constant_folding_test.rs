@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+
+use webnn_onnx_utils::ConversionError;
+use webnn_onnx_utils::attributes::AttrBuilder;
+use webnn_onnx_utils::constant_folding::ConstantFolder;
+use webnn_onnx_utils::data_types::DataType;
+use webnn_onnx_utils::protos::onnx::attribute_proto::AttributeType;
+use webnn_onnx_utils::protos::onnx::tensor_proto::DataLocation;
+use webnn_onnx_utils::protos::onnx::{AttributeProto, NodeProto, TensorProto};
+use webnn_onnx_utils::shape_inference::{Dim, ShapeInferenceContext, TensorShape};
+use webnn_onnx_utils::tensor::Tensor;
+use webnn_onnx_utils::tensor_data::{Scalar, TensorData};
+
+fn node(op_type: &str, inputs: &[&str], output: &str, attribute: Vec<AttributeProto>) -> NodeProto {
+    NodeProto {
+        op_type: op_type.to_string(),
+        input: inputs.iter().map(|s| s.to_string()).collect(),
+        output: vec![output.to_string()],
+        attribute,
+        ..Default::default()
+    }
+}
+
+fn int64(shape: Vec<i64>, values: Vec<i64>) -> Tensor {
+    TensorData::Int64(values)
+        .attach(DataType::Int64, shape)
+        .unwrap()
+}
+
+fn float32(shape: Vec<i64>, values: Vec<f32>) -> Tensor {
+    TensorData::Float32(values)
+        .attach(DataType::Float32, shape)
+        .unwrap()
+}
+
+fn fold_one(folder: &mut ConstantFolder, node: &NodeProto) -> Tensor {
+    let mut ctx = ShapeInferenceContext::new();
+    assert!(folder.fold_node(node, &mut ctx).unwrap());
+    folder.get(&node.output[0]).unwrap().clone()
+}
+
+#[test]
+fn test_fold_shape_gather_unsqueeze_concat_chain() {
+    let mut ctx = ShapeInferenceContext::with_overrides(HashMap::from([
+        ("batch".to_string(), 2),
+        ("height".to_string(), 4),
+    ]));
+    ctx.set_shape(
+        "x".to_string(),
+        TensorShape::new(vec![
+            Dim::Dynamic("batch".to_string()),
+            Dim::Known(3),
+            Dim::Dynamic("height".to_string()),
+        ]),
+    );
+
+    let mut folder = ConstantFolder::new();
+    folder.insert("zero", int64(vec![], vec![0]));
+    folder.insert("axes", int64(vec![1], vec![0]));
+    folder.insert("minus_one", int64(vec![1], vec![-1]));
+    let nodes = vec![
+        node("Shape", &["x"], "shape", vec![]),
+        node("Gather", &["shape", "zero"], "batch", vec![]),
+        node("Unsqueeze", &["batch", "axes"], "batch_1d", vec![]),
+        node(
+            "Concat",
+            &["batch_1d", "minus_one"],
+            "target",
+            AttrBuilder::new().add_int("axis", 0).build(),
+        ),
+        node("Relu", &["x"], "y", vec![]),
+    ];
+
+    let folded = folder.fold_nodes(&nodes, &mut ctx).unwrap();
+    assert_eq!(folded, vec![0, 1, 2, 3]);
+    let target = folder.get("target").unwrap();
+    assert_eq!(target.shape(), &[2]);
+    assert_eq!(target.data().to_vec_i64().unwrap(), vec![2, -1]);
+    assert_eq!(
+        ctx.get_shape("target"),
+        Some(&TensorShape::from_static(vec![2]))
+    );
+
+    let proto = folder.to_initializer("target").unwrap();
+    assert_eq!(proto.name, "target");
+    assert_eq!(proto.dims, vec![2]);
+}
+
+#[test]
+fn test_fold_skips_unknown_inputs() {
+    let mut ctx = ShapeInferenceContext::new();
+    let mut folder = ConstantFolder::new();
+    // no static shape for x
+    assert!(
+        !folder
+            .fold_node(&node("Shape", &["x"], "s", vec![]), &mut ctx)
+            .unwrap()
+    );
+    assert!(
+        !folder
+            .fold_node(&node("Add", &["a", "b"], "c", vec![]), &mut ctx)
+            .unwrap()
+    );
+    assert!(folder.get("s").is_none());
+}
+
+#[test]
+fn test_fold_gather_and_slice() {
+    let mut folder = ConstantFolder::new();
+    folder.insert(
+        "data",
+        float32(vec![2, 3], vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0]),
+    );
+    folder.insert("idx", int64(vec![2], vec![-1, 0]));
+    let gathered = fold_one(
+        &mut folder,
+        &node(
+            "Gather",
+            &["data", "idx"],
+            "g",
+            AttrBuilder::new().add_int("axis", 1).build(),
+        ),
+    );
+    assert_eq!(gathered.shape(), &[2, 2]);
+    assert_eq!(
+        gathered.data().to_vec_f32().unwrap(),
+        vec![2.0, 0.0, 5.0, 3.0]
+    );
+
+    folder.insert("starts", int64(vec![1], vec![-1]));
+    folder.insert("ends", int64(vec![1], vec![i64::MIN]));
+    folder.insert("axes", int64(vec![1], vec![1]));
+    folder.insert("steps", int64(vec![1], vec![-2]));
+    let sliced = fold_one(
+        &mut folder,
+        &node(
+            "Slice",
+            &["data", "starts", "ends", "axes", "steps"],
+            "s",
+            vec![],
+        ),
+    );
+    assert_eq!(sliced.shape(), &[2, 2]);
+    assert_eq!(
+        sliced.data().to_vec_f32().unwrap(),
+        vec![2.0, 0.0, 5.0, 3.0]
+    );
+}
+
+#[test]
+fn test_fold_squeeze_unsqueeze_and_cast() {
+    let mut folder = ConstantFolder::new();
+    folder.insert("x", int64(vec![1, 3, 1], vec![1, -2, 300]));
+
+    let squeezed = fold_one(&mut folder, &node("Squeeze", &["x"], "sq", vec![]));
+    assert_eq!(squeezed.shape(), &[3]);
+
+    let unsqueezed = fold_one(
+        &mut folder,
+        &node(
+            "Unsqueeze",
+            &["sq"],
+            "un",
+            AttrBuilder::new().add_ints("axes", vec![-1]).build(),
+        ),
+    );
+    assert_eq!(unsqueezed.shape(), &[3, 1]);
+
+    let cast = fold_one(
+        &mut folder,
+        &node(
+            "Cast",
+            &["sq"],
+            "c",
+            AttrBuilder::new().add_int("to", 3).build(),
+        ), // INT8
+    );
+    assert_eq!(cast.dtype(), &DataType::Int8);
+    assert_eq!(cast.data().to_vec_i64().unwrap(), vec![1, -2, 44]);
+}
+
+#[test]
+fn test_fold_arithmetic_with_broadcasting() {
+    let mut folder = ConstantFolder::new();
+    folder.insert("a", int64(vec![2, 2], vec![7, -7, 9, 4]));
+    folder.insert("b", int64(vec![2], vec![2, 4]));
+
+    let div = fold_one(&mut folder, &node("Div", &["a", "b"], "d", vec![]));
+    assert_eq!(div.data().to_vec_i64().unwrap(), vec![3, -1, 4, 1]);
+    let mul = fold_one(&mut folder, &node("Mul", &["a", "b"], "m", vec![]));
+    assert_eq!(mul.data().to_vec_i64().unwrap(), vec![14, -28, 18, 16]);
+
+    folder.insert("f", float32(vec![], vec![0.5]));
+    folder.insert("g", float32(vec![3], vec![1.0, 2.0, 3.0]));
+    let sub = fold_one(&mut folder, &node("Sub", &["g", "f"], "s", vec![]));
+    assert_eq!(sub.shape(), &[3]);
+    assert_eq!(sub.data().to_vec_f32().unwrap(), vec![0.5, 1.5, 2.5]);
+
+    // mixed dtypes are rejected
+    let mut ctx = ShapeInferenceContext::new();
+    assert!(
+        folder
+            .fold_node(&node("Add", &["a", "g"], "bad", vec![]), &mut ctx)
+            .is_err()
+    );
+    folder.insert("zero", int64(vec![], vec![0]));
+    assert!(
+        folder
+            .fold_node(&node("Div", &["a", "zero"], "bad", vec![]), &mut ctx)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_fold_range_constant_of_shape_equal_where() {
+    let mut folder = ConstantFolder::new();
+    folder.insert("start", int64(vec![], vec![10]));
+    folder.insert("limit", int64(vec![], vec![1]));
+    folder.insert("delta", int64(vec![], vec![-4]));
+    let range = fold_one(
+        &mut folder,
+        &node("Range", &["start", "limit", "delta"], "r", vec![]),
+    );
+    assert_eq!(range.data().to_vec_i64().unwrap(), vec![10, 6, 2]);
+
+    folder.insert("shape", int64(vec![2], vec![2, 3]));
+    let value = TensorData::Int64(vec![1])
+        .to_tensor_proto(String::new(), DataType::Int64, vec![1])
+        .unwrap();
+    let ones = fold_one(
+        &mut folder,
+        &node(
+            "ConstantOfShape",
+            &["shape"],
+            "ones",
+            AttrBuilder::new().add_tensor("value", value).build(),
+        ),
+    );
+    assert_eq!(ones.shape(), &[2, 3]);
+    assert_eq!(ones.get(&[1, 2]), Some(Scalar::Int(1)));
+
+    let zeros = fold_one(
+        &mut folder,
+        &node("ConstantOfShape", &["shape"], "zeros", vec![]),
+    );
+    assert_eq!(zeros.dtype(), &DataType::Float32);
+
+    folder.insert("neg", int64(vec![], vec![-1]));
+    folder.insert("dims", int64(vec![3], vec![4, -1, 5]));
+    folder.insert("inferred", int64(vec![3], vec![4, 6, 5]));
+    let is_neg = fold_one(&mut folder, &node("Equal", &["dims", "neg"], "eq", vec![]));
+    assert_eq!(is_neg.dtype(), &DataType::Bool);
+    let resolved = fold_one(
+        &mut folder,
+        &node("Where", &["eq", "inferred", "dims"], "w", vec![]),
+    );
+    assert_eq!(resolved.data().to_vec_i64().unwrap(), vec![4, 6, 5]);
+}
+
+#[test]
+fn test_fold_constant_node() {
+    let mut folder = ConstantFolder::new();
+    let c = fold_one(
+        &mut folder,
+        &node(
+            "Constant",
+            &[],
+            "c",
+            AttrBuilder::new()
+                .add_ints("value_ints", vec![1, 2])
+                .build(),
+        ),
+    );
+    assert_eq!(c.data().to_vec_i64().unwrap(), vec![1, 2]);
+}
+
+#[test]
+fn test_fold_leaves_unsupported_nodes_in_place() {
+    let external = TensorProto {
+        name: "weight".to_string(),
+        data_type: 1, // FLOAT
+        dims: vec![1024],
+        data_location: DataLocation::External as i32,
+        ..Default::default()
+    };
+    let labels = TensorProto {
+        name: "labels".to_string(),
+        data_type: 8, // STRING
+        dims: vec![1],
+        string_data: vec![b"cat".to_vec()],
+        ..Default::default()
+    };
+    let big = TensorData::Uint64(vec![u64::MAX])
+        .into_tensor_proto("big".to_string(), DataType::Uint64, vec![1])
+        .unwrap();
+    let mut folder = ConstantFolder::from_initializers(&[external, labels, big]).unwrap();
+    assert!(folder.get("weight").is_none());
+    assert!(folder.get("labels").is_none());
+    folder.insert("dims", int64(vec![2], vec![2, 3]));
+
+    let sparse = AttributeProto {
+        name: "sparse_value".to_string(),
+        r#type: AttributeType::SparseTensor as i32,
+        ..Default::default()
+    };
+    let strings = AttributeProto {
+        name: "value_strings".to_string(),
+        r#type: AttributeType::Strings as i32,
+        strings: vec![b"a".to_vec()],
+        ..Default::default()
+    };
+    let nodes = vec![
+        node("Constant", &[], "sparse", vec![sparse]),
+        node("Constant", &[], "strings", vec![strings]),
+        node(
+            "Cast",
+            &["dims"],
+            "dims_str",
+            AttrBuilder::new().add_int("to", 8).build(),
+        ),
+        node("Add", &["big", "big"], "bigger", vec![]),
+        node("Shape", &["weight"], "weight_shape", vec![]),
+        node("Mul", &["dims", "dims"], "squared", vec![]),
+    ];
+    let mut ctx = ShapeInferenceContext::new();
+    assert_eq!(folder.fold_nodes(&nodes, &mut ctx).unwrap(), vec![5]);
+    assert_eq!(
+        folder.get("squared").unwrap().data().to_vec_i64().unwrap(),
+        vec![4, 9]
+    );
+
+    // a Constant without any value is still malformed
+    let result = folder.fold_node(&node("Constant", &[], "c", vec![]), &mut ctx);
+    assert!(matches!(result, Err(ConversionError::InvalidAttribute(_))));
+}
@@ -1,4 +1,6 @@
-use webnn_onnx_utils::data_types::{DataType, onnx_to_webnn, webnn_to_onnx};
+use webnn_onnx_utils::data_types::{
+    DataType, TypeLoweringPolicy, is_webnn_native, onnx_to_webnn, webnn_lowering, webnn_to_onnx,
+};
 
 #[test]
 fn round_trip_dtype_codes() {
@@ -11,6 +13,21 @@ fn round_trip_dtype_codes() {
         DataType::Uint64,
         DataType::Int8,
         DataType::Uint8,
+        DataType::Int4,
+        DataType::Uint4,
+        DataType::Bool,
+        DataType::Int16,
+        DataType::Uint16,
+        DataType::Float64,
+        DataType::BFloat16,
+        DataType::Float8E4M3FN,
+        DataType::Float8E4M3FNUZ,
+        DataType::Float8E5M2,
+        DataType::Float8E5M2FNUZ,
+        DataType::Float8E8M0,
+        DataType::Float4E2M1,
+        DataType::Int2,
+        DataType::Uint2,
     ];
 
     for dt in all {
@@ -19,3 +36,155 @@ fn round_trip_dtype_codes() {
         assert_eq!(dt, back);
     }
 }
+
+#[test]
+fn onnx_only_types_are_decoded() {
+    assert_eq!(onnx_to_webnn(9).unwrap(), DataType::Bool);
+    assert_eq!(onnx_to_webnn(11).unwrap(), DataType::Float64);
+    assert_eq!(onnx_to_webnn(16).unwrap(), DataType::BFloat16);
+    assert_eq!(onnx_to_webnn(22).unwrap(), DataType::Int4);
+    assert!(onnx_to_webnn(8).is_err()); // STRING
+}
+
+#[test]
+fn lowering_of_onnx_only_types() {
+    assert!(is_webnn_native(&DataType::Int4));
+    assert!(!is_webnn_native(&DataType::Bool));
+
+    assert_eq!(webnn_lowering(&DataType::Bool), DataType::Uint8);
+    assert_eq!(webnn_lowering(&DataType::Int16), DataType::Int32);
+    assert_eq!(webnn_lowering(&DataType::Uint16), DataType::Uint32);
+    assert_eq!(webnn_lowering(&DataType::Float64), DataType::Float32);
+    assert_eq!(webnn_lowering(&DataType::BFloat16), DataType::Float32);
+    assert_eq!(webnn_lowering(&DataType::Int64), DataType::Int64);
+    assert_eq!(webnn_lowering(&DataType::Float8E5M2), DataType::Float16);
+    assert_eq!(webnn_lowering(&DataType::Float8E8M0), DataType::Float32);
+}
+
+#[test]
+fn lowering_policy_defaults() {
+    let policy = TypeLoweringPolicy::new();
+    assert_eq!(policy.lower(&DataType::Int64).unwrap(), DataType::Int64);
+    assert_eq!(policy.lower(&DataType::Bool).unwrap(), DataType::Uint8);
+    assert_eq!(policy.lower(&DataType::Float64).unwrap(), DataType::Float32);
+    assert!(policy.is_narrowing(&DataType::Float64).unwrap());
+    assert!(!policy.is_narrowing(&DataType::Int64).unwrap());
+}
+
+#[test]
+fn lowering_policy_without_int64() {
+    let policy = TypeLoweringPolicy::new()
+        .without(DataType::Int64)
+        .without(DataType::Uint64);
+    assert_eq!(policy.lower(&DataType::Int64).unwrap(), DataType::Int32);
+    assert_eq!(policy.lower(&DataType::Uint64).unwrap(), DataType::Uint32);
+    assert!(policy.is_narrowing(&DataType::Int64).unwrap());
+}
+
+#[test]
+fn lowering_policy_chains_fallbacks() {
+    let policy = TypeLoweringPolicy::with_supported(&[DataType::Float32, DataType::Int32]);
+    assert_eq!(policy.lower(&DataType::Bool).unwrap(), DataType::Int32);
+    assert_eq!(policy.lower(&DataType::Int4).unwrap(), DataType::Int32);
+    assert!(policy.lower(&DataType::Uint64).is_err());
+}
+
+#[test]
+fn dtype_sizes() {
+    assert_eq!(DataType::Float32.byte_size(), 4);
+    assert_eq!(DataType::BFloat16.bit_width(), 16);
+    assert_eq!(DataType::Int4.bit_width(), 4);
+    assert_eq!(DataType::Int4.byte_size(), 1);
+    assert_eq!(DataType::Uint4.storage_len(3), 2);
+    assert_eq!(DataType::Int64.storage_len(3), 24);
+    assert_eq!(DataType::Int2.storage_len(5), 2);
+}
+
+#[test]
+fn dtype_classification() {
+    assert!(DataType::Float16.is_floating());
+    assert!(DataType::Float16.is_signed());
+    assert!(!DataType::Int8.is_floating());
+    assert!(DataType::Int8.is_integer());
+    assert!(!DataType::Bool.is_integer());
+    assert!(!DataType::Uint32.is_signed());
+}
+
+#[test]
+fn dtype_limits() {
+    assert_eq!(DataType::Int4.min_value(), -8.0);
+    assert_eq!(DataType::Uint4.max_value(), 15.0);
+    assert_eq!(DataType::Int8.min_value(), -128.0);
+    assert_eq!(DataType::Float16.max_value(), 65504.0);
+    assert_eq!(DataType::Bool.max_value(), 1.0);
+    assert_eq!(DataType::Float8E4M3FN.min_value(), -448.0);
+    assert_eq!(DataType::Float4E2M1.max_value(), 6.0);
+}
+
+#[test]
+fn dtype_names_round_trip() {
+    assert_eq!(DataType::Float32.to_string(), "float32");
+    assert_eq!("uint8".parse::<DataType>().unwrap(), DataType::Uint8);
+    assert_eq!("bfloat16".parse::<DataType>().unwrap(), DataType::BFloat16);
+    assert!("Float32".parse::<DataType>().is_err());
+}
+
+#[test]
+fn serde_uses_webnn_spelling() {
+    assert_eq!(
+        serde_json::to_string(&DataType::Float32).unwrap(),
+        "\"float32\""
+    );
+    assert_eq!(
+        serde_json::to_string(&DataType::BFloat16).unwrap(),
+        "\"bfloat16\""
+    );
+
+    let all = [
+        DataType::Float32,
+        DataType::Float16,
+        DataType::Int32,
+        DataType::Uint32,
+        DataType::Int64,
+        DataType::Uint64,
+        DataType::Int8,
+        DataType::Uint8,
+        DataType::Int4,
+        DataType::Uint4,
+        DataType::Bool,
+        DataType::Int16,
+        DataType::Uint16,
+        DataType::Float64,
+        DataType::BFloat16,
+        DataType::Float8E4M3FN,
+        DataType::Float8E4M3FNUZ,
+        DataType::Float8E5M2,
+        DataType::Float8E5M2FNUZ,
+        DataType::Float8E8M0,
+        DataType::Float4E2M1,
+        DataType::Int2,
+        DataType::Uint2,
+    ];
+    for dt in all {
+        let json = serde_json::to_string(&dt).unwrap();
+        assert_eq!(json, format!("\"{dt}\""));
+        assert_eq!(serde_json::from_str::<DataType>(&json).unwrap(), dt);
+    }
+}
+
+#[test]
+fn serde_accepts_onnx_names() {
+    let parsed: Vec<DataType> =
+        serde_json::from_str(r#"["FLOAT", "INT64", "DOUBLE", "FLOAT8E4M3FN", "uint8"]"#).unwrap();
+    assert_eq!(
+        parsed,
+        vec![
+            DataType::Float32,
+            DataType::Int64,
+            DataType::Float64,
+            DataType::Float8E4M3FN,
+            DataType::Uint8
+        ]
+    );
+    assert!(serde_json::from_str::<DataType>("\"Float32\"").is_err());
+}
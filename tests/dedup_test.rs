@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use webnn_onnx_utils::data_types::DataType;
+use webnn_onnx_utils::dedup::{ContentHash, dedup_initializers};
+use webnn_onnx_utils::protos::onnx::TensorProto;
+use webnn_onnx_utils::tensor_data::{PackedBits, TensorData};
+
+fn float_initializer(name: &str, dims: Vec<i64>, values: Vec<f32>) -> TensorProto {
+    TensorData::Float32(values)
+        .into_tensor_proto(name.to_string(), DataType::Float32, dims)
+        .unwrap()
+}
+
+#[test]
+fn test_content_hash_is_stable_and_encoding_independent() {
+    let typed = TensorData::Float32(vec![1.0, 2.0]);
+    let hash = typed.content_hash(&DataType::Float32, &[2]).unwrap();
+    // pinned so that a change to the hashed layout is noticed
+    assert_eq!(hash, ContentHash(0x180e_5b5e_c97c_f4aa));
+    assert_eq!(hash.to_string(), "180e5b5ec97cf4aa");
+
+    let raw = TensorData::Raw(typed.as_bytes().into_owned());
+    assert_eq!(raw.content_hash(&DataType::Float32, &[2]).unwrap(), hash);
+
+    assert_ne!(
+        typed.content_hash(&DataType::Float32, &[1, 2]).unwrap(),
+        hash
+    );
+    let as_ints = TensorData::Int32(vec![0x3f80_0000, 0x4000_0000]);
+    assert_ne!(as_ints.content_hash(&DataType::Int32, &[2]).unwrap(), hash);
+
+    assert!(typed.content_hash(&DataType::Float32, &[3]).is_err());
+}
+
+#[test]
+fn test_content_hash_ignores_packed_padding() {
+    let clean = TensorData::Uint4(PackedBits {
+        bytes: vec![0x21, 0x03],
+        len: 3,
+    });
+    let dirty = TensorData::Uint4(PackedBits {
+        bytes: vec![0x21, 0xf3],
+        len: 3,
+    });
+    assert_eq!(
+        clean.content_hash(&DataType::Uint4, &[3]).unwrap(),
+        dirty.content_hash(&DataType::Uint4, &[3]).unwrap()
+    );
+}
+
+#[test]
+fn test_dedup_initializers() {
+    let mut typed_bias = TensorProto {
+        name: "bias_typed".to_string(),
+        data_type: 1, // FLOAT
+        dims: vec![3],
+        float_data: vec![0.0; 3],
+        ..Default::default()
+    };
+    let initializers = vec![
+        float_initializer("bias_a", vec![3], vec![0.0; 3]),
+        float_initializer("weight", vec![3], vec![1.0, 2.0, 3.0]),
+        float_initializer("bias_b", vec![3], vec![0.0; 3]),
+        float_initializer("bias_matrix", vec![1, 3], vec![0.0; 3]),
+        typed_bias.clone(),
+        float_initializer("weight_copy", vec![3], vec![1.0, 2.0, 3.0]),
+    ];
+    let aliases = dedup_initializers(&initializers).unwrap();
+    let expected: HashMap<String, String> = [
+        ("bias_b", "bias_a"),
+        ("bias_typed", "bias_a"),
+        ("weight_copy", "weight"),
+    ]
+    .into_iter()
+    .map(|(a, b)| (a.to_string(), b.to_string()))
+    .collect();
+    assert_eq!(aliases, expected);
+
+    // same values but a different type are kept apart
+    typed_bias.data_type = 7; // INT64
+    typed_bias.float_data.clear();
+    typed_bias.int64_data = vec![0; 3];
+    let aliases = dedup_initializers(&[initializers[0].clone(), typed_bias]).unwrap();
+    assert!(aliases.is_empty());
+
+    // string initializers are skipped rather than failing the pass
+    let label = TensorProto {
+        name: "label".to_string(),
+        data_type: 8, // STRING
+        dims: vec![1],
+        string_data: vec![b"cat".to_vec()],
+        ..Default::default()
+    };
+    let mut label_copy = label.clone();
+    label_copy.name = "label_copy".to_string();
+    let aliases = dedup_initializers(&[label, label_copy, initializers[1].clone()]).unwrap();
+    assert!(aliases.is_empty());
+}
@@ -0,0 +1,167 @@
+use std::path::PathBuf;
+
+use webnn_onnx_utils::data_types::DataType;
+use webnn_onnx_utils::external_data::{
+    EXTERNAL_DATA_ALIGNMENT, ExternalDataInfo, ExternalDataResolver, ExternalDataWriter,
+};
+use webnn_onnx_utils::protos::onnx::TensorProto;
+use webnn_onnx_utils::protos::onnx::tensor_proto::DataLocation;
+use webnn_onnx_utils::tensor_data::TensorData;
+
+fn scratch_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("webnn-onnx-utils-{test}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn weights(name: &str, count: usize) -> TensorProto {
+    let values: Vec<f32> = (0..count).map(|i| i as f32).collect();
+    TensorData::Float32(values)
+        .to_tensor_proto(name.to_string(), DataType::Float32, vec![count as i64])
+        .unwrap()
+}
+
+#[test]
+fn test_spill_and_load_round_trip() {
+    let dir = scratch_dir("round-trip");
+    let mut big = weights("big", 1024);
+    let mut small = weights("small", 4);
+    let mut second = weights("second", 512);
+
+    let mut writer = ExternalDataWriter::create(&dir, "model.data")
+        .unwrap()
+        .with_size_threshold(1024);
+    assert!(writer.spill(&mut big).unwrap());
+    assert!(!writer.spill(&mut small).unwrap());
+    assert!(writer.spill(&mut second).unwrap());
+    writer.finish().unwrap();
+
+    assert_eq!(big.data_location, DataLocation::External as i32);
+    assert!(big.raw_data.is_empty());
+    assert_eq!(small.data_location, DataLocation::Default as i32);
+
+    let info = ExternalDataInfo::from_tensor_proto(&second)
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.location, "model.data");
+    assert_eq!(info.offset % EXTERNAL_DATA_ALIGNMENT, 0);
+    assert_eq!(info.length, Some(2048));
+
+    let resolver = ExternalDataResolver::new(&dir);
+    match resolver.load(&second).unwrap() {
+        TensorData::Raw(bytes) => match TensorData::decode(&bytes, &DataType::Float32).unwrap() {
+            TensorData::Float32(v) => assert_eq!(v[511], 511.0),
+            other => panic!("Expected Float32 variant, got {other:?}"),
+        },
+        other => panic!("Expected Raw variant, got {other:?}"),
+    }
+    assert_eq!(resolver.load(&small).unwrap().byte_len(), 16);
+
+    resolver.inline(&mut big).unwrap();
+    assert_eq!(big.data_location, DataLocation::Default as i32);
+    assert_eq!(big, weights("big", 1024));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_spill_converts_typed_fields() {
+    let dir = scratch_dir("typed-fields");
+    let mut tensor = TensorProto {
+        name: "ids".to_string(),
+        data_type: 7, // INT64
+        dims: vec![2],
+        int64_data: vec![7, -7],
+        ..Default::default()
+    };
+    let mut writer = ExternalDataWriter::create(&dir, "ids.bin")
+        .unwrap()
+        .with_size_threshold(0);
+    assert!(writer.spill(&mut tensor).unwrap());
+    writer.finish().unwrap();
+    assert!(tensor.int64_data.is_empty());
+
+    let data = ExternalDataResolver::new(&dir).load(&tensor).unwrap();
+    match data.to_typed(&DataType::Int64).unwrap() {
+        TensorData::Int64(v) => assert_eq!(v, vec![7, -7]),
+        other => panic!("Expected Int64 variant, got {other:?}"),
+    }
+
+    // nothing to spill for placeholders without data or for strings
+    let mut placeholder = TensorProto {
+        name: "placeholder".to_string(),
+        data_type: 1, // FLOAT
+        dims: vec![4],
+        ..Default::default()
+    };
+    let mut labels = TensorProto {
+        name: "labels".to_string(),
+        data_type: 8, // STRING
+        dims: vec![1],
+        string_data: vec![b"cat".to_vec()],
+        ..Default::default()
+    };
+    let (placeholder_before, labels_before) = (placeholder.clone(), labels.clone());
+    let mut writer = ExternalDataWriter::create(&dir, "empty.bin")
+        .unwrap()
+        .with_size_threshold(0);
+    assert!(!writer.spill(&mut placeholder).unwrap());
+    assert!(!writer.spill(&mut labels).unwrap());
+    assert_eq!(placeholder, placeholder_before);
+    assert_eq!(labels, labels_before);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_external_tensor_requires_resolver() {
+    let mut tensor = weights("w", 4);
+    tensor.raw_data.clear();
+    tensor.data_location = DataLocation::External as i32;
+    tensor.external_data = ExternalDataInfo {
+        location: "w.bin".to_string(),
+        offset: 0,
+        length: Some(16),
+        checksum: None,
+    }
+    .to_entries();
+
+    assert!(TensorData::from_tensor_proto(&tensor).is_err());
+    // the side file does not exist
+    assert!(
+        ExternalDataResolver::new(std::env::temp_dir())
+            .load(&tensor)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_external_data_rejects_bad_locations_and_ranges() {
+    let dir = scratch_dir("bad-locations");
+    std::fs::write(dir.join("short.bin"), [0u8; 8]).unwrap();
+    let resolver = ExternalDataResolver::new(&dir);
+
+    let escaping = ExternalDataInfo {
+        location: "../secret.bin".to_string(),
+        offset: 0,
+        length: None,
+        checksum: None,
+    };
+    assert!(resolver.read_bytes(&escaping).is_err());
+
+    let too_long = ExternalDataInfo {
+        location: "short.bin".to_string(),
+        offset: 4,
+        length: Some(8),
+        checksum: None,
+    };
+    assert!(resolver.read_bytes(&too_long).is_err());
+
+    let tail = ExternalDataInfo {
+        length: None,
+        ..too_long
+    };
+    assert_eq!(resolver.read_bytes(&tail).unwrap().len(), 4);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,90 @@
+use std::cmp::Ordering;
+
+use webnn_onnx_utils::ConversionError;
+use webnn_onnx_utils::data_types::DataType;
+use webnn_onnx_utils::half_float::{FloatStats, HalfFormat};
+use webnn_onnx_utils::tensor_data::TensorData;
+
+#[test]
+fn test_half_bulk_conversion() {
+    let values = [0.0, 1.0, -2.5, 65504.0, 1e6, 1.0e-7, f32::NAN];
+    let data = TensorData::float16_from_f32(&values);
+    let TensorData::Float16(bits) = &data else {
+        panic!("expected Float16");
+    };
+    assert_eq!(&bits[..5], &[0x0000, 0x3c00, 0xc100, 0x7bff, 0x7c00]);
+    assert_eq!(bits[5], 0x0002); // subnormal
+    assert!(HalfFormat::F16.is_nan(bits[6]));
+
+    let back = data.to_vec_f32().unwrap();
+    assert_eq!(&back[..4], &[0.0, 1.0, -2.5, 65504.0]);
+    assert_eq!(back[4], f32::INFINITY);
+    assert!(back[6].is_nan());
+
+    let data = TensorData::bfloat16_from_f32(&[1.0, -3.0e38, 1.0 + 1.0 / 256.0]);
+    let TensorData::BFloat16(bits) = &data else {
+        panic!("expected BFloat16");
+    };
+    // 1 + 2^-8 is a tie and rounds to even
+    assert_eq!(bits, &[0x3f80, 0xff62, 0x3f80]);
+    assert_eq!(data.to_vec_f32().unwrap()[0], 1.0);
+}
+
+#[test]
+fn test_half_comparison() {
+    let f16 = HalfFormat::F16;
+    assert!(f16.ieee_eq(0x0000, 0x8000));
+    assert!(!f16.ieee_eq(0x7e00, 0x7e00));
+    assert!(f16.is_infinite(0xfc00));
+    assert!(!f16.is_nan(0x7c00));
+
+    assert_eq!(f16.total_cmp(0x8000, 0x0000), Ordering::Less);
+    assert_eq!(f16.total_cmp(0xbc00, 0x3c00), Ordering::Less);
+    assert_eq!(f16.total_cmp(0xc000, 0xbc00), Ordering::Less);
+    assert_eq!(f16.total_cmp(0x7c00, 0x7e00), Ordering::Less);
+
+    assert_eq!(f16.ulp_distance(0x3c00, 0x3c01), Some(1));
+    assert_eq!(f16.ulp_distance(0x0001, 0x8001), Some(2));
+    assert_eq!(f16.ulp_distance(0x0000, 0x8000), Some(0));
+    assert_eq!(f16.ulp_distance(0x3c00, 0x7e00), None);
+
+    // bf16 NaNs start above 0x7f80, where f16 would see a finite value
+    assert!(HalfFormat::BF16.is_nan(0x7fc0));
+    assert!(!HalfFormat::F16.is_nan(0x7bff));
+    assert_eq!(
+        HalfFormat::from_data_type(&DataType::BFloat16),
+        Some(HalfFormat::BF16)
+    );
+}
+
+#[test]
+fn test_float_stats() {
+    let data =
+        TensorData::float16_from_f32(&[1.0, -2.0, f32::NAN, f32::INFINITY, 4.0, -f32::INFINITY]);
+    let stats = data.float_stats().unwrap();
+    assert_eq!(
+        stats,
+        FloatStats {
+            count: 6,
+            nan_count: 1,
+            inf_count: 2,
+            min: Some(-2.0),
+            max: Some(4.0),
+            mean: Some(1.0),
+        }
+    );
+
+    let stats = TensorData::Float32(vec![f32::NAN]).float_stats().unwrap();
+    assert_eq!((stats.min, stats.mean), (None, None));
+
+    let stats = TensorData::Float8E4M3FN(vec![0x38, 0x7f])
+        .float_stats()
+        .unwrap();
+    assert_eq!((stats.max, stats.nan_count), (Some(1.0), 1));
+
+    let result = TensorData::Int32(vec![1]).float_stats();
+    assert!(matches!(
+        result,
+        Err(ConversionError::UnsupportedDataType(_))
+    ));
+}
@@ -0,0 +1,125 @@
+use webnn_onnx_utils::ConversionError;
+use webnn_onnx_utils::data_types::DataType;
+use webnn_onnx_utils::layout::{FilterDims, FilterLayout, InputLayout};
+use webnn_onnx_utils::tensor::Tensor;
+use webnn_onnx_utils::tensor_data::TensorData;
+
+fn iota(shape: Vec<i64>) -> Tensor {
+    let count = shape.iter().product::<i64>();
+    TensorData::Int64((0..count).collect())
+        .attach(DataType::Int64, shape)
+        .unwrap()
+}
+
+#[test]
+fn test_transpose() {
+    let t = iota(vec![2, 3]).transpose(&[1, 0]).unwrap();
+    assert_eq!(t.shape(), &[3, 2]);
+    assert_eq!(t.data().to_vec_i64().unwrap(), vec![0, 3, 1, 4, 2, 5]);
+
+    let t = iota(vec![2, 3, 4]).transpose(&[2, 0, 1]).unwrap();
+    assert_eq!(t.shape(), &[4, 2, 3]);
+    assert_eq!(t.get(&[3, 1, 2]), iota(vec![2, 3, 4]).get(&[1, 2, 3]));
+
+    // sub-byte elements move individually
+    let packed = TensorData::Uint4(webnn_onnx_utils::tensor_data::PackedBits::pack(
+        &[1, 2, 3, 4, 5, 6],
+        4,
+    ))
+    .attach(DataType::Uint4, vec![3, 2])
+    .unwrap();
+    let t = packed.transpose(&[1, 0]).unwrap();
+    assert_eq!(t.data().to_vec_i64().unwrap(), vec![1, 3, 5, 2, 4, 6]);
+
+    for perm in [&[0, 0][..], &[0][..], &[0, 2][..]] {
+        assert!(matches!(
+            iota(vec![2, 3]).transpose(perm),
+            Err(ConversionError::InvalidAttribute(_))
+        ));
+    }
+}
+
+#[test]
+fn test_convert_input_layout() {
+    let nchw = iota(vec![1, 2, 3, 4]);
+    let nhwc = nchw
+        .convert_input_layout(InputLayout::Nchw, InputLayout::Nhwc)
+        .unwrap();
+    assert_eq!(nhwc.shape(), &[1, 3, 4, 2]);
+    assert_eq!(nhwc.get(&[0, 2, 1, 1]), nchw.get(&[0, 1, 2, 1]));
+
+    let back = nhwc
+        .convert_input_layout(InputLayout::Nhwc, InputLayout::Nchw)
+        .unwrap();
+    assert_eq!(
+        back.data().to_vec_i64().unwrap(),
+        nchw.data().to_vec_i64().unwrap()
+    );
+    assert!(
+        iota(vec![2, 3])
+            .convert_input_layout(InputLayout::Nchw, InputLayout::Nhwc)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_convert_filter_layout() {
+    // 4 output channels, 3 input channels, 2x5 kernel
+    let oihw = iota(vec![4, 3, 2, 5]);
+    for (layout, shape) in [
+        (FilterLayout::Hwio, [2, 5, 3, 4]),
+        (FilterLayout::Ohwi, [4, 2, 5, 3]),
+        (FilterLayout::Ihwo, [3, 2, 5, 4]),
+    ] {
+        let converted = oihw
+            .convert_filter_layout(FilterLayout::Oihw, layout, 1)
+            .unwrap();
+        assert_eq!(converted.shape(), &shape);
+        assert_eq!(
+            layout.filter_dims(converted.shape()),
+            FilterDims {
+                output_channels: 4,
+                input_channels_per_group: 3,
+                height: 2,
+                width: 5,
+            }
+        );
+        let back = converted
+            .convert_filter_layout(layout, FilterLayout::Oihw, 1)
+            .unwrap();
+        assert_eq!(
+            back.data().to_vec_i64().unwrap(),
+            oihw.data().to_vec_i64().unwrap()
+        );
+    }
+
+    let hwio = oihw
+        .convert_filter_layout(FilterLayout::Oihw, FilterLayout::Hwio, 1)
+        .unwrap();
+    assert_eq!(hwio.get(&[1, 4, 2, 3]), oihw.get(&[3, 2, 1, 4]));
+    let ohwi = hwio
+        .convert_filter_layout(FilterLayout::Hwio, FilterLayout::Ohwi, 1)
+        .unwrap();
+    assert_eq!(ohwi.get(&[3, 1, 4, 2]), oihw.get(&[3, 2, 1, 4]));
+}
+
+#[test]
+fn test_convert_grouped_and_depthwise_filters() {
+    // depthwise over 3 channels with multiplier 2
+    let depthwise = iota(vec![6, 1, 3, 3]);
+    let ihwo = depthwise
+        .convert_filter_layout(FilterLayout::Oihw, FilterLayout::Ihwo, 3)
+        .unwrap();
+    assert_eq!(ihwo.shape(), &[1, 3, 3, 6]);
+    assert_eq!(ihwo.get(&[0, 2, 1, 5]), depthwise.get(&[5, 0, 2, 1]));
+
+    // 6 output channels cannot form 4 groups
+    assert!(matches!(
+        depthwise.convert_filter_layout(FilterLayout::Oihw, FilterLayout::Ihwo, 4),
+        Err(ConversionError::InvalidTensorData(_))
+    ));
+
+    assert_eq!(FilterLayout::from_name("ohwi"), Some(FilterLayout::Ohwi));
+    assert_eq!(InputLayout::Nhwc.name(), "nhwc");
+    assert_eq!(FilterLayout::from_name("iohw"), None);
+}
@@ -0,0 +1,94 @@
+use webnn_onnx_utils::minifloat::{MiniFloatFormat, decode, encode};
+
+const FORMATS: [MiniFloatFormat; 6] = [
+    MiniFloatFormat::E4M3FN,
+    MiniFloatFormat::E4M3FNUZ,
+    MiniFloatFormat::E5M2,
+    MiniFloatFormat::E5M2FNUZ,
+    MiniFloatFormat::E8M0,
+    MiniFloatFormat::E2M1,
+];
+
+#[test]
+fn test_every_code_round_trips() {
+    for format in FORMATS {
+        let codes = if format == MiniFloatFormat::E2M1 {
+            0..16u16
+        } else {
+            0..256u16
+        };
+        for code in codes {
+            let code = code as u8;
+            let value = decode(format, code);
+            if value.is_nan() {
+                assert_eq!(Some(encode(format, value, false)), format.nan_code());
+                continue;
+            }
+            assert_eq!(
+                encode(format, value, false),
+                code,
+                "{format:?} code {code:#04x} decoded to {value}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_decode_known_values() {
+    assert_eq!(decode(MiniFloatFormat::E4M3FN, 0x38), 1.0);
+    assert_eq!(decode(MiniFloatFormat::E4M3FN, 0x7E), 448.0);
+    assert_eq!(decode(MiniFloatFormat::E4M3FN, 0x01), 2f32.powi(-9));
+    assert!(decode(MiniFloatFormat::E4M3FN, 0xFF).is_nan());
+    assert_eq!(decode(MiniFloatFormat::E4M3FNUZ, 0x7F), 240.0);
+    assert!(decode(MiniFloatFormat::E4M3FNUZ, 0x80).is_nan());
+    assert_eq!(decode(MiniFloatFormat::E5M2, 0x7C), f32::INFINITY);
+    assert_eq!(decode(MiniFloatFormat::E5M2, 0x7B), 57344.0);
+    assert_eq!(decode(MiniFloatFormat::E5M2FNUZ, 0x7F), 57344.0);
+    assert_eq!(decode(MiniFloatFormat::E8M0, 127), 1.0);
+    assert_eq!(decode(MiniFloatFormat::E8M0, 0), 2f32.powi(-127));
+    assert_eq!(decode(MiniFloatFormat::E2M1, 0x7), 6.0);
+    assert_eq!(decode(MiniFloatFormat::E2M1, 0xD), -3.0);
+}
+
+#[test]
+fn test_encode_rounds_to_nearest_even() {
+    // 1.0625 lies halfway between 1.0 (0x38) and 1.125 (0x39)
+    assert_eq!(encode(MiniFloatFormat::E4M3FN, 1.0625, true), 0x38);
+    // 1.1875 lies halfway between 1.125 (0x39) and 1.25 (0x3A)
+    assert_eq!(encode(MiniFloatFormat::E4M3FN, 1.1875, true), 0x3A);
+    assert_eq!(encode(MiniFloatFormat::E2M1, 2.5, true), 0x4);
+    assert_eq!(encode(MiniFloatFormat::E2M1, 0.2, true), 0x0);
+}
+
+#[test]
+fn test_encode_saturation() {
+    assert_eq!(encode(MiniFloatFormat::E4M3FN, 1000.0, true), 0x7E);
+    assert_eq!(encode(MiniFloatFormat::E4M3FN, -1000.0, true), 0xFE);
+    assert_eq!(encode(MiniFloatFormat::E4M3FN, 1000.0, false), 0x7F);
+    assert_eq!(encode(MiniFloatFormat::E4M3FN, f32::INFINITY, true), 0x7E);
+    assert_eq!(encode(MiniFloatFormat::E4M3FNUZ, 1000.0, false), 0x80);
+    assert_eq!(encode(MiniFloatFormat::E5M2, 1e6, false), 0x7C);
+    assert_eq!(encode(MiniFloatFormat::E5M2, -1e6, false), 0xFC);
+    assert_eq!(encode(MiniFloatFormat::E5M2, f32::INFINITY, true), 0x7B);
+    assert_eq!(
+        encode(MiniFloatFormat::E5M2FNUZ, f32::INFINITY, false),
+        0x80
+    );
+    assert_eq!(encode(MiniFloatFormat::E2M1, 100.0, false), 0x7);
+}
+
+#[test]
+fn test_encode_zero_handling() {
+    assert_eq!(encode(MiniFloatFormat::E4M3FN, -0.0, true), 0x80);
+    assert_eq!(encode(MiniFloatFormat::E4M3FNUZ, -0.0, true), 0x00);
+    assert_eq!(encode(MiniFloatFormat::E5M2FNUZ, -1e-30, true), 0x00);
+}
+
+#[test]
+fn test_encode_e8m0_rounds_up() {
+    assert_eq!(encode(MiniFloatFormat::E8M0, 1.0, true), 127);
+    assert_eq!(encode(MiniFloatFormat::E8M0, 1.5, true), 128);
+    assert_eq!(encode(MiniFloatFormat::E8M0, f32::NAN, true), 0xFF);
+    assert_eq!(encode(MiniFloatFormat::E8M0, f32::MAX, true), 0xFE);
+    assert_eq!(encode(MiniFloatFormat::E8M0, f32::MAX, false), 0xFF);
+}
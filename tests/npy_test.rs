@@ -0,0 +1,155 @@
+use std::io::Cursor;
+
+use webnn_onnx_utils::ConversionError;
+use webnn_onnx_utils::data_types::DataType;
+use webnn_onnx_utils::npy::{read_npy, read_npz, write_npy, write_npz};
+use webnn_onnx_utils::tensor::Tensor;
+use webnn_onnx_utils::tensor_data::TensorData;
+
+/// Builds an `.npy` file by hand, the way NumPy lays it out.
+fn npy_file(version: u8, header: &str, data: &[u8]) -> Vec<u8> {
+    let mut file = b"\x93NUMPY".to_vec();
+    file.extend([version, 0]);
+    if version == 1 {
+        file.extend((header.len() as u16).to_le_bytes());
+    } else {
+        file.extend((header.len() as u32).to_le_bytes());
+    }
+    file.extend(header.as_bytes());
+    file.extend(data);
+    file
+}
+
+#[test]
+fn test_npy_round_trip() {
+    let tensor = TensorData::Float32(vec![1.0, -2.5, 3.0, 4.0, 5.0, 6.0])
+        .attach(DataType::Float32, vec![2, 3])
+        .unwrap();
+    let mut file = Vec::new();
+    write_npy(&mut file, &tensor).unwrap();
+
+    assert_eq!(&file[..8], b"\x93NUMPY\x01\x00");
+    let header_len = u16::from_le_bytes([file[8], file[9]]) as usize;
+    assert_eq!((10 + header_len) % 64, 0);
+    let header = std::str::from_utf8(&file[10..10 + header_len]).unwrap();
+    assert!(header.starts_with("{'descr': '<f4', 'fortran_order': False, 'shape': (2, 3), }"));
+    assert!(header.ends_with('\n'));
+
+    let read = read_npy(file.as_slice()).unwrap();
+    assert_eq!(read.dtype(), &DataType::Float32);
+    assert_eq!(read.shape(), &[2, 3]);
+    assert_eq!(
+        read.data().as_slice::<f32>(),
+        tensor.data().as_slice::<f32>()
+    );
+}
+
+#[test]
+fn test_npy_round_trip_scalar_and_vector_shapes() {
+    for shape in [vec![], vec![3], vec![0, 4]] {
+        let tensor = Tensor::filled(DataType::Int64, shape.clone(), 7.0).unwrap();
+        let mut file = Vec::new();
+        write_npy(&mut file, &tensor).unwrap();
+        let read = read_npy(file.as_slice()).unwrap();
+        assert_eq!(read.shape(), shape.as_slice());
+        assert_eq!(
+            read.data().to_vec_i64().unwrap(),
+            tensor.data().to_vec_i64().unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_npy_reads_big_endian_and_v2_headers() {
+    let header = "{'descr': '>i4', 'fortran_order': False, 'shape': (3,), }\n";
+    let data = [0, 0, 0, 1, 0, 0, 1, 0, 0xff, 0xff, 0xff, 0xfe];
+    let tensor = read_npy(npy_file(2, header, &data).as_slice()).unwrap();
+    assert_eq!(tensor.dtype(), &DataType::Int32);
+    assert_eq!(tensor.data().as_slice::<i32>(), Some(&[1, 256, -2][..]));
+
+    let header = "{'descr': '|b1', 'fortran_order': False, 'shape': (2,), }\n";
+    let tensor = read_npy(npy_file(1, header, &[1, 0]).as_slice()).unwrap();
+    assert_eq!(tensor.data().as_slice::<bool>(), Some(&[true, false][..]));
+}
+
+#[test]
+fn test_npy_rejects_unsupported_input() {
+    let header = "{'descr': '<f4', 'fortran_order': True, 'shape': (2, 2), }\n";
+    let result = read_npy(npy_file(1, header, &[0; 16]).as_slice());
+    assert!(matches!(result, Err(ConversionError::InvalidTensorData(_))));
+
+    let header = "{'descr': '<c8', 'fortran_order': False, 'shape': (1,), }\n";
+    let result = read_npy(npy_file(1, header, &[0; 8]).as_slice());
+    assert!(matches!(
+        result,
+        Err(ConversionError::UnsupportedDataType(_))
+    ));
+
+    // truncated payload
+    let header = "{'descr': '<i8', 'fortran_order': False, 'shape': (2,), }\n";
+    let result = read_npy(npy_file(1, header, &[0; 8]).as_slice());
+    assert!(matches!(result, Err(ConversionError::Io(_))));
+
+    let tensor = Tensor::scalar(DataType::BFloat16, 1.0);
+    let result = write_npy(Vec::new(), &tensor);
+    assert!(matches!(
+        result,
+        Err(ConversionError::UnsupportedDataType(_))
+    ));
+}
+
+#[test]
+fn test_npz_round_trip() {
+    let weights = TensorData::Float16(vec![0x3c00, 0x4000])
+        .attach(DataType::Float16, vec![2])
+        .unwrap();
+    let ids = TensorData::Uint8(vec![1, 2, 3, 4])
+        .attach(DataType::Uint8, vec![2, 2])
+        .unwrap();
+    let archive = write_npz(
+        Cursor::new(Vec::new()),
+        &[("weights", &weights), ("ids", &ids)],
+    )
+    .unwrap()
+    .into_inner();
+
+    let arrays = read_npz(Cursor::new(archive)).unwrap();
+    let names: Vec<&str> = arrays.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["weights", "ids"]);
+    assert_eq!(arrays[0].1.dtype(), &DataType::Float16);
+    assert_eq!(arrays[0].1.data().as_bytes(), weights.data().as_bytes());
+    assert_eq!(arrays[1].1.shape(), &[2, 2]);
+    assert_eq!(arrays[1].1.data().as_slice::<u8>(), Some(&[1, 2, 3, 4][..]));
+
+    let result = read_npz(Cursor::new(b"not a zip".to_vec()));
+    assert!(matches!(result, Err(ConversionError::InvalidTensorData(_))));
+}
+
+#[test]
+fn test_npy_header_version_near_u16_limit() {
+    // ranks whose header crosses the 65535 byte limit of version 1
+    let mut versions = vec![];
+    for rank in 21_820..21_850 {
+        let tensor = Tensor::new(
+            DataType::Float32,
+            vec![1; rank],
+            TensorData::Float32(vec![1.0]),
+        )
+        .unwrap();
+        let mut bytes = Vec::new();
+        write_npy(&mut bytes, &tensor).unwrap();
+
+        let version = bytes[6];
+        let (prefix_len, header_len) = if version == 1 {
+            (10, u16::from_le_bytes([bytes[8], bytes[9]]) as usize)
+        } else {
+            let len = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+            (12, len as usize)
+        };
+        assert_eq!((prefix_len + header_len) % 64, 0);
+        assert_eq!(bytes.len(), prefix_len + header_len + 4);
+        assert_eq!(read_npy(bytes.as_slice()).unwrap().rank(), rank);
+        versions.push(version);
+    }
+    assert!(versions.contains(&1) && versions.contains(&2));
+}
@@ -0,0 +1,115 @@
+use webnn_onnx_utils::ConversionError;
+use webnn_onnx_utils::data_types::DataType;
+use webnn_onnx_utils::operand_descriptor::OperandDescriptor;
+use webnn_onnx_utils::protos::onnx::tensor_shape_proto::{Dimension, dimension};
+use webnn_onnx_utils::protos::onnx::{TensorShapeProto, TypeProto, ValueInfoProto, type_proto};
+use webnn_onnx_utils::shape_inference::{Dim, TensorShape};
+
+fn dim(value: Option<dimension::Value>) -> Dimension {
+    Dimension {
+        value,
+        ..Default::default()
+    }
+}
+
+fn tensor_value_info(name: &str, elem_type: i32, shape: Option<Vec<Dimension>>) -> ValueInfoProto {
+    ValueInfoProto {
+        name: name.to_string(),
+        r#type: Some(TypeProto {
+            value: Some(type_proto::Value::TensorType(type_proto::Tensor {
+                elem_type,
+                shape: shape.map(|dim| TensorShapeProto { dim }),
+            })),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_descriptor_from_value_info() {
+    let info = tensor_value_info(
+        "input",
+        1, // FLOAT
+        Some(vec![
+            dim(Some(dimension::Value::DimParam("batch".to_string()))),
+            dim(Some(dimension::Value::DimValue(3))),
+            dim(None),
+        ]),
+    );
+
+    let desc = OperandDescriptor::try_from(&info).unwrap();
+    assert_eq!(desc.data_type, DataType::Float32);
+    assert_eq!(
+        desc.shape.dims,
+        vec![
+            Dim::Dynamic("batch".to_string()),
+            Dim::Known(3),
+            Dim::Dynamic(String::new())
+        ]
+    );
+}
+
+#[test]
+fn test_descriptor_scalar() {
+    let info = tensor_value_info("s", 7, Some(vec![])); // INT64
+    let desc = OperandDescriptor::try_from(&info).unwrap();
+    assert_eq!(desc.data_type, DataType::Int64);
+    assert_eq!(desc.shape.rank(), 0);
+}
+
+#[test]
+fn test_descriptor_round_trip() {
+    let desc = OperandDescriptor::new(
+        DataType::Float16,
+        TensorShape::new(vec![
+            Dim::Dynamic("n".to_string()),
+            Dim::Known(224),
+            Dim::Dynamic(String::new()),
+        ]),
+    );
+
+    let info = desc.to_value_info("x");
+    assert_eq!(info.name, "x");
+    assert_eq!(OperandDescriptor::try_from(&info).unwrap(), desc);
+
+    let unnamed: ValueInfoProto = desc.clone().into();
+    assert!(unnamed.name.is_empty());
+    assert_eq!(OperandDescriptor::try_from(&unnamed).unwrap(), desc);
+}
+
+#[test]
+fn test_descriptor_rejects_non_tensor_types() {
+    let sequence = ValueInfoProto {
+        name: "seq".to_string(),
+        r#type: Some(TypeProto {
+            value: Some(type_proto::Value::SequenceType(Box::new(
+                type_proto::Sequence { elem_type: None },
+            ))),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    match OperandDescriptor::try_from(&sequence) {
+        Err(ConversionError::UnsupportedValueType(msg)) => assert!(msg.contains("seq")),
+        other => panic!("Expected UnsupportedValueType, got {other:?}"),
+    }
+
+    let untyped = ValueInfoProto {
+        name: "untyped".to_string(),
+        ..Default::default()
+    };
+    assert!(OperandDescriptor::try_from(&untyped).is_err());
+}
+
+#[test]
+fn test_descriptor_rejects_unknown_rank_and_type() {
+    let no_shape = tensor_value_info("x", 1, None);
+    assert!(OperandDescriptor::try_from(&no_shape).is_err());
+
+    let string = tensor_value_info("x", 8, Some(vec![])); // STRING
+    assert!(matches!(
+        OperandDescriptor::try_from(&string),
+        Err(ConversionError::UnsupportedOnnxDataType(8))
+    ));
+}
@@ -0,0 +1,121 @@
+use webnn_onnx_utils::ConversionError;
+use webnn_onnx_utils::data_types::DataType;
+use webnn_onnx_utils::quantization::{Granularity, QuantParams};
+use webnn_onnx_utils::tensor::Tensor;
+use webnn_onnx_utils::tensor_data::TensorData;
+
+fn floats(values: Vec<f32>, shape: Vec<i64>) -> Tensor {
+    TensorData::Float32(values)
+        .attach(DataType::Float32, shape)
+        .unwrap()
+}
+
+#[test]
+fn test_quantize_per_tensor() {
+    // the example from the ONNX QuantizeLinear documentation
+    let x = floats(vec![0.0, 2.0, 3.0, 1000.0, -254.0, -1000.0], vec![2, 3]);
+    let params = QuantParams::per_tensor(2.0, 128);
+    let q = x.quantize(&params, DataType::Uint8).unwrap();
+    assert_eq!(q.shape(), &[2, 3]);
+    assert_eq!(
+        q.data().as_slice::<u8>(),
+        Some(&[128, 129, 130, 255, 1, 0][..])
+    );
+
+    let dq = q.dequantize(&params, DataType::Float32).unwrap();
+    assert_eq!(
+        dq.data().as_slice::<f32>(),
+        Some(&[0.0, 2.0, 4.0, 254.0, -254.0, -256.0][..])
+    );
+
+    // ties round to even, NaN becomes the zero point
+    let x = floats(vec![0.5, 1.5, 2.5, -0.5, f32::NAN], vec![5]);
+    let q = x
+        .quantize(&QuantParams::per_tensor(1.0, 0), DataType::Int8)
+        .unwrap();
+    assert_eq!(q.data().as_slice::<i8>(), Some(&[0, 2, 2, 0, 0][..]));
+}
+
+#[test]
+fn test_quantize_per_axis_int4() {
+    let x = floats(vec![1.0, 2.0, 3.0, -4.0, 100.0, 8.0], vec![2, 3]);
+    let params = QuantParams::per_axis(0, vec![1.0, 4.0], vec![0, -1]);
+    let q = x.quantize(&params, DataType::Int4).unwrap();
+    assert_eq!(q.data().to_vec_i64().unwrap(), vec![1, 2, 3, -2, 7, 1]);
+
+    let dq = q.dequantize(&params, DataType::Float16).unwrap();
+    assert_eq!(dq.dtype(), &DataType::Float16);
+    assert_eq!(
+        dq.data().to_vec_f32().unwrap(),
+        vec![1.0, 2.0, 3.0, -4.0, 32.0, 8.0]
+    );
+
+    let wrong = QuantParams::per_axis(1, vec![1.0, 4.0], vec![0, 0]);
+    assert!(matches!(
+        x.quantize(&wrong, DataType::Int4),
+        Err(ConversionError::InvalidTensorData(_))
+    ));
+}
+
+#[test]
+fn test_quantize_blocked() {
+    // blocks of 2 along axis 1 of a [2, 5] tensor: scales have shape [2, 3]
+    let x = floats((0..10).map(|v| v as f32).collect(), vec![2, 5]);
+    let params = QuantParams::blocked(
+        1,
+        2,
+        vec![1.0, 2.0, 4.0, 1.0, 2.0, 4.0],
+        vec![0, 0, 0, 0, 0, 8],
+    );
+    let q = x.quantize(&params, DataType::Uint4).unwrap();
+    assert_eq!(
+        q.data().to_vec_i64().unwrap(),
+        vec![0, 1, 1, 2, 1, 5, 6, 4, 4, 10]
+    );
+
+    let scale = floats(vec![1.0, 2.0, 4.0, 1.0, 2.0, 4.0], vec![2, 3]);
+    let from_onnx = QuantParams::from_onnx(&[2, 5], &scale, None, 1, 2).unwrap();
+    assert_eq!(
+        from_onnx.granularity,
+        Granularity::Blocked {
+            axis: 1,
+            block_size: 2
+        }
+    );
+    assert!(QuantParams::from_onnx(&[2, 5], &scale, None, 1, 0).is_err());
+
+    // a 1-D input with a 1-D scale is blocked, not per-axis, once block_size is set
+    let x = floats(vec![1.0, 2.0, 8.0, 12.0], vec![4]);
+    let scale = floats(vec![1.0, 4.0], vec![2]);
+    let params = QuantParams::from_onnx(&[4], &scale, None, 0, 2).unwrap();
+    assert_eq!(
+        params.granularity,
+        Granularity::Blocked {
+            axis: 0,
+            block_size: 2
+        }
+    );
+    let q = x.quantize(&params, DataType::Int8).unwrap();
+    assert_eq!(q.data().to_vec_i64().unwrap(), vec![1, 2, 2, 3]);
+}
+
+#[test]
+fn test_quant_params_from_min_max() {
+    let params = QuantParams::from_min_max(-1.0, 3.0, &DataType::Uint8, false).unwrap();
+    assert_eq!(params.scale, vec![4.0 / 255.0]);
+    assert_eq!(params.zero_point, vec![64]);
+
+    let params = QuantParams::from_min_max(-1.0, 2.0, &DataType::Int8, true).unwrap();
+    assert_eq!(params.scale, vec![4.0 / 255.0]);
+    assert_eq!(params.zero_point, vec![0]);
+    let params = QuantParams::from_min_max(-1.0, 2.0, &DataType::Uint4, true).unwrap();
+    assert_eq!(params.zero_point, vec![8]);
+
+    let params = QuantParams::from_min_max(0.0, 0.0, &DataType::Int4, false).unwrap();
+    assert_eq!(params, QuantParams::per_tensor(1.0, 0));
+
+    assert!(matches!(
+        QuantParams::from_min_max(0.0, 1.0, &DataType::Int32, false),
+        Err(ConversionError::UnsupportedDataType(_))
+    ));
+}
@@ -0,0 +1,154 @@
+use webnn_onnx_utils::ConversionError;
+use webnn_onnx_utils::data_types::DataType;
+use webnn_onnx_utils::protos::onnx::TensorProto;
+use webnn_onnx_utils::safetensors::Safetensors;
+use webnn_onnx_utils::tensor::Tensor;
+use webnn_onnx_utils::tensor_data::TensorData;
+
+/// Builds a safetensors file from a JSON header and buffer.
+fn safetensors_file(header: &str, buffer: &[u8]) -> Vec<u8> {
+    let mut file = (header.len() as u64).to_le_bytes().to_vec();
+    file.extend(header.as_bytes());
+    file.extend(buffer);
+    file
+}
+
+#[test]
+fn test_safetensors_round_trip() {
+    let bias = TensorData::Float16(vec![0x3c00, 0x4000, 0x4200])
+        .attach(DataType::Float16, vec![3])
+        .unwrap();
+    let weight = TensorData::Float32(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0])
+        .attach(DataType::Float32, vec![2, 3])
+        .unwrap();
+    let mut file = Safetensors {
+        tensors: vec![("bias".to_string(), bias), ("weight".to_string(), weight)],
+        ..Default::default()
+    };
+    file.metadata.insert("format".to_string(), "pt".to_string());
+
+    let mut bytes = Vec::new();
+    file.write(&mut bytes).unwrap();
+    let header_len = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+    assert_eq!(header_len % 8, 0);
+    assert_eq!(bytes.len(), 8 + header_len + 24 + 6);
+    let header = std::str::from_utf8(&bytes[8..8 + header_len]).unwrap();
+    assert!(header.contains(r#""dtype":"F32""#));
+    assert!(header.contains(r#""data_offsets":[0,24]"#));
+
+    let read = Safetensors::read(bytes.as_slice()).unwrap();
+    assert_eq!(read.metadata.get("format").map(String::as_str), Some("pt"));
+    // wider types come first in the buffer
+    let names: Vec<&str> = read.tensors.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, ["weight", "bias"]);
+    let weight = read.get("weight").unwrap();
+    assert_eq!(weight.shape(), &[2, 3]);
+    assert_eq!(
+        weight.data().as_slice::<f32>(),
+        Some(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0][..])
+    );
+    assert_eq!(read.get("bias").unwrap().dtype(), &DataType::Float16);
+}
+
+#[test]
+fn test_safetensors_round_trip_with_empty_tensor() {
+    let a = TensorData::Float32(vec![1.0, 2.0])
+        .attach(DataType::Float32, vec![2])
+        .unwrap();
+    // the wider empty tensor is written first, at [0, 0], and sorts after
+    // "a" at [0, 8] in the header
+    let empty = TensorData::Float64(vec![])
+        .attach(DataType::Float64, vec![0])
+        .unwrap();
+    let file = Safetensors {
+        tensors: vec![("a".to_string(), a), ("b_empty".to_string(), empty)],
+        ..Default::default()
+    };
+    let mut bytes = Vec::new();
+    file.write(&mut bytes).unwrap();
+
+    let read = Safetensors::read(bytes.as_slice()).unwrap();
+    assert_eq!(read.get("b_empty").unwrap().shape(), &[0]);
+    assert_eq!(
+        read.get("a").unwrap().data().as_slice::<f32>(),
+        Some(&[1.0, 2.0][..])
+    );
+}
+
+#[test]
+fn test_safetensors_from_initializers() {
+    let init = TensorProto {
+        name: "w".to_string(),
+        data_type: 7, // INT64
+        dims: vec![2],
+        int64_data: vec![10, -20],
+        ..Default::default()
+    };
+    let file = Safetensors::from_initializers(&[init]).unwrap();
+    let mut bytes = Vec::new();
+    file.write(&mut bytes).unwrap();
+
+    let read = Safetensors::read(bytes.as_slice()).unwrap();
+    assert!(read.metadata.is_empty());
+    assert_eq!(
+        read.get("w").unwrap().data().as_slice::<i64>(),
+        Some(&[10, -20][..])
+    );
+}
+
+#[test]
+fn test_safetensors_accepts_crate_dtype_names() {
+    let header = r#"{"x":{"dtype":"uint8","shape":[2],"data_offsets":[0,2]}}"#;
+    let read = Safetensors::read(safetensors_file(header, &[7, 8]).as_slice()).unwrap();
+    assert_eq!(
+        read.get("x").unwrap().data().as_slice::<u8>(),
+        Some(&[7, 8][..])
+    );
+}
+
+#[test]
+fn test_safetensors_rejects_invalid_files() {
+    // size does not match the shape
+    let header = r#"{"x":{"dtype":"F32","shape":[2],"data_offsets":[0,4]}}"#;
+    let result = Safetensors::read(safetensors_file(header, &[0; 4]).as_slice());
+    assert!(matches!(result, Err(ConversionError::InvalidTensorData(_))));
+
+    // gap between tensors
+    let header = r#"{"a":{"dtype":"U8","shape":[1],"data_offsets":[0,1]},
+                     "b":{"dtype":"U8","shape":[1],"data_offsets":[2,3]}}"#;
+    let result = Safetensors::read(safetensors_file(header, &[0; 3]).as_slice());
+    assert!(matches!(result, Err(ConversionError::InvalidTensorData(_))));
+
+    // buffer shorter than the offsets, or longer
+    let header = r#"{"x":{"dtype":"F32","shape":[2],"data_offsets":[0,8]}}"#;
+    let result = Safetensors::read(safetensors_file(header, &[0; 6]).as_slice());
+    assert!(matches!(result, Err(ConversionError::InvalidTensorData(_))));
+    let result = Safetensors::read(safetensors_file(header, &[0; 9]).as_slice());
+    assert!(matches!(result, Err(ConversionError::InvalidTensorData(_))));
+
+    let header = r#"{"x":{"dtype":"float8e4m3fnuz","shape":[1],"data_offsets":[0,1]}}"#;
+    let result = Safetensors::read(safetensors_file(header, &[0; 1]).as_slice());
+    assert!(matches!(
+        result,
+        Err(ConversionError::UnsupportedDataType(_))
+    ));
+
+    let header = r#"{"x":{"dtype":"C64","shape":[1],"data_offsets":[0,8]}}"#;
+    let result = Safetensors::read(safetensors_file(header, &[0; 8]).as_slice());
+    assert!(matches!(
+        result,
+        Err(ConversionError::UnsupportedDataType(_))
+    ));
+
+    let file = Safetensors {
+        tensors: vec![(
+            "q".to_string(),
+            Tensor::filled(DataType::Int4, vec![2], 1.0).unwrap(),
+        )],
+        ..Default::default()
+    };
+    assert!(matches!(
+        file.write(Vec::new()),
+        Err(ConversionError::UnsupportedDataType(_))
+    ));
+}
@@ -0,0 +1,137 @@
+use std::io::Cursor;
+
+use prost::Message;
+use webnn_onnx_utils::ConversionError;
+use webnn_onnx_utils::protos::onnx::tensor_proto::DataLocation;
+use webnn_onnx_utils::protos::onnx::{
+    GraphProto, ModelProto, NodeProto, StringStringEntryProto, TensorProto,
+};
+use webnn_onnx_utils::streaming::{InitializerReader, PayloadField};
+use webnn_onnx_utils::tensor_data::TensorData;
+
+fn model_bytes(initializers: Vec<TensorProto>) -> Vec<u8> {
+    ModelProto {
+        ir_version: 9,
+        producer_name: "test".to_string(),
+        graph: Some(GraphProto {
+            name: "graph".to_string(),
+            node: vec![NodeProto {
+                op_type: "Add".to_string(),
+                input: vec!["x".to_string(), "bias".to_string()],
+                output: vec!["y".to_string()],
+                ..Default::default()
+            }],
+            initializer: initializers,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+    .encode_to_vec()
+}
+
+#[test]
+fn test_streaming_lists_initializers() {
+    let raw = TensorProto {
+        name: "bias".to_string(),
+        data_type: 1, // FLOAT
+        dims: vec![2, 3],
+        raw_data: TensorData::Float32(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0])
+            .as_bytes()
+            .into_owned(),
+        ..Default::default()
+    };
+    let typed = TensorProto {
+        name: "shape".to_string(),
+        data_type: 7, // INT64
+        dims: vec![3],
+        int64_data: vec![1, -1, 300],
+        ..Default::default()
+    };
+    let external = TensorProto {
+        name: "weight".to_string(),
+        data_type: 1,
+        dims: vec![1024],
+        data_location: DataLocation::External as i32,
+        external_data: vec![StringStringEntryProto {
+            key: "location".to_string(),
+            value: "weights.bin".to_string(),
+        }],
+        ..Default::default()
+    };
+    let bytes = model_bytes(vec![raw.clone(), typed.clone(), external.clone()]);
+
+    let infos: Vec<_> = InitializerReader::new(Cursor::new(&bytes))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(infos.len(), 3);
+
+    assert_eq!(infos[0].name, "bias");
+    assert_eq!(infos[0].dims, vec![2, 3]);
+    assert_eq!(infos[0].data_type, 1);
+    let payload = infos[0].payload.unwrap();
+    assert_eq!(payload.field, PayloadField::RawData);
+    let range = payload.offset as usize..(payload.offset + payload.length) as usize;
+    assert_eq!(&bytes[range], raw.raw_data.as_slice());
+
+    assert_eq!(infos[1].payload.unwrap().field, PayloadField::Int64Data);
+    assert_eq!(
+        infos[1].read_tensor_proto(Cursor::new(&bytes)).unwrap(),
+        typed
+    );
+    let tensor = infos[0].read_tensor(Cursor::new(&bytes)).unwrap();
+    assert_eq!(tensor.shape(), &[2, 3]);
+    assert_eq!(
+        tensor.data().to_vec_f32().unwrap(),
+        vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+    );
+
+    assert_eq!(infos[2].payload, None);
+    assert_eq!(infos[2].data_location, DataLocation::External as i32);
+    assert_eq!(infos[2].external_data, external.external_data);
+}
+
+#[test]
+fn test_streaming_accepts_unpacked_dims() {
+    // proto2 writers emit each dim as its own varint field
+    let mut tensor = vec![0x08, 0x02, 0x08, 0x03]; // dims: 2, dims: 3
+    tensor.extend([0x10, 0x01]); // data_type: FLOAT
+    tensor.extend([0x42, 0x01, b'w']); // name: "w"
+    let mut graph = vec![0x2a, tensor.len() as u8];
+    graph.extend(&tensor);
+    let mut model = vec![0x08, 0x07]; // ir_version: 7
+    model.extend([0x3a, graph.len() as u8]);
+    model.extend(&graph);
+
+    let infos: Vec<_> = InitializerReader::new(Cursor::new(model))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(infos.len(), 1);
+    assert_eq!(infos[0].name, "w");
+    assert_eq!(infos[0].dims, vec![2, 3]);
+    assert_eq!(infos[0].payload, None);
+}
+
+#[test]
+fn test_streaming_rejects_truncated_models() {
+    let bytes = model_bytes(vec![TensorProto {
+        name: "bias".to_string(),
+        data_type: 1,
+        dims: vec![4],
+        float_data: vec![1.0; 4],
+        ..Default::default()
+    }]);
+    let truncated = &bytes[..bytes.len() - 4];
+    let mut reader = InitializerReader::new(Cursor::new(truncated)).unwrap();
+    assert!(matches!(
+        reader.next(),
+        Some(Err(ConversionError::InvalidTensorData(_)))
+    ));
+    assert!(reader.next().is_none());
+
+    let empty: Vec<_> = InitializerReader::new(Cursor::new(model_bytes(vec![])))
+        .unwrap()
+        .collect();
+    assert!(empty.is_empty());
+}
@@ -1,10 +1,16 @@
-use webnn_onnx_utils::data_types::DataType;
-use webnn_onnx_utils::tensor_data::TensorData;
+use webnn_onnx_utils::ConversionError;
+use webnn_onnx_utils::data_types::{DataType, TypeLoweringPolicy};
+use webnn_onnx_utils::protos::onnx::{SparseTensorProto, TensorProto};
+use webnn_onnx_utils::tensor_data::{
+    CountValidation, PackedBits, Scalar, SparseIndexLayout, SparseTensorBuilder, TensorData,
+    TensorView, element_count_of,
+};
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_tensor_data_scalar_float32() {
     let data = TensorData::scalar(DataType::Float32, 3.14);
-    assert_eq!(data.len(), 1);
+    assert_eq!(data.element_count(&DataType::Float32).unwrap(), 1);
 
     match data {
         TensorData::Float32(ref v) => assert_eq!(v[0], 3.14),
@@ -15,7 +21,7 @@ fn test_tensor_data_scalar_float32() {
 #[test]
 fn test_tensor_data_scalar_int32() {
     let data = TensorData::scalar(DataType::Int32, 42.0);
-    assert_eq!(data.len(), 1);
+    assert_eq!(data.element_count(&DataType::Int32).unwrap(), 1);
 
     match data {
         TensorData::Int32(ref v) => assert_eq!(v[0], 42),
@@ -26,7 +32,7 @@ fn test_tensor_data_scalar_int32() {
 #[test]
 fn test_tensor_data_filled_float32() {
     let data = TensorData::filled(DataType::Float32, &[2, 3], 1.0);
-    assert_eq!(data.len(), 6);
+    assert_eq!(data.element_count(&DataType::Float32).unwrap(), 6);
 
     match data {
         TensorData::Float32(ref v) => {
@@ -40,7 +46,7 @@ fn test_tensor_data_filled_float32() {
 #[test]
 fn test_tensor_data_filled_uint8() {
     let data = TensorData::filled(DataType::Uint8, &[4, 4], 255.0);
-    assert_eq!(data.len(), 16);
+    assert_eq!(data.element_count(&DataType::Uint8).unwrap(), 16);
 
     match data {
         TensorData::Uint8(ref v) => {
@@ -74,9 +80,25 @@ fn test_tensor_data_as_bytes_uint8() {
 
 #[test]
 fn test_tensor_data_len() {
-    assert_eq!(TensorData::Float32(vec![1.0, 2.0, 3.0]).len(), 3);
-    assert_eq!(TensorData::Int32(vec![1, 2]).len(), 2);
-    assert_eq!(TensorData::Uint8(vec![1]).len(), 1);
+    let floats = TensorData::Float32(vec![1.0, 2.0, 3.0]);
+    assert_eq!(floats.element_count(&DataType::Float32).unwrap(), 3);
+    assert_eq!(floats.byte_len(), 12);
+    assert!(floats.element_count(&DataType::Int32).is_err());
+
+    let raw = TensorData::Raw(vec![0; 8]);
+    assert_eq!(raw.element_count(&DataType::Int32).unwrap(), 2);
+    assert_eq!(raw.element_count(&DataType::Int4).unwrap(), 16);
+    assert!(
+        TensorData::Raw(vec![0; 6])
+            .element_count(&DataType::Int32)
+            .is_err()
+    );
+    assert_eq!(raw.byte_len(), 8);
+
+    let int4 = TensorData::Int4(PackedBits::pack_signed(&[1, 2, 3], 4));
+    assert_eq!(int4.element_count(&DataType::Int4).unwrap(), 3);
+    assert_eq!(int4.byte_len(), 2);
+    assert_eq!(TensorData::Bool(vec![true]).byte_len(), 1);
 }
 
 #[test]
@@ -88,7 +110,9 @@ fn test_tensor_data_is_empty() {
 #[test]
 fn test_tensor_data_to_tensor_proto_float32() {
     let data = TensorData::Float32(vec![1.0, 2.0, 3.0]);
-    let proto = data.to_tensor_proto("test".to_string(), DataType::Float32, vec![3]);
+    let proto = data
+        .to_tensor_proto("test".to_string(), DataType::Float32, vec![3])
+        .unwrap();
 
     assert_eq!(proto.name, "test");
     assert_eq!(proto.dims, vec![3]);
@@ -98,7 +122,9 @@ fn test_tensor_data_to_tensor_proto_float32() {
 #[test]
 fn test_tensor_data_to_tensor_proto_int64() {
     let data = TensorData::Int64(vec![100, 200, 300]);
-    let proto = data.to_tensor_proto("weights".to_string(), DataType::Int64, vec![1, 3]);
+    let proto = data
+        .to_tensor_proto("weights".to_string(), DataType::Int64, vec![1, 3])
+        .unwrap();
 
     assert_eq!(proto.name, "weights");
     assert_eq!(proto.dims, vec![1, 3]);
@@ -115,11 +141,675 @@ fn test_tensor_data_all_types() {
         (DataType::Uint32, 5.0),
         (DataType::Uint64, 6.0),
         (DataType::Uint8, 7.0),
+        (DataType::Int4, -3.0),
+        (DataType::Uint4, 9.0),
+        (DataType::Bool, 1.0),
+        (DataType::Int16, 8.0),
+        (DataType::Uint16, 10.0),
+        (DataType::Float64, 11.0),
+        (DataType::BFloat16, 12.0),
     ];
 
     for (dtype, value) in types {
-        let data = TensorData::scalar(dtype, value);
-        assert_eq!(data.len(), 1);
+        let data = TensorData::scalar(dtype.clone(), value);
+        assert_eq!(data.element_count(&dtype).unwrap(), 1);
         assert!(!data.is_empty());
     }
 }
+
+#[test]
+fn test_tensor_data_from_tensor_proto_bool() {
+    let proto = TensorProto {
+        data_type: 9, // BOOL
+        dims: vec![3],
+        int32_data: vec![1, 0, 1],
+        ..Default::default()
+    };
+    match TensorData::from_tensor_proto(&proto).unwrap() {
+        TensorData::Bool(v) => assert_eq!(v, vec![true, false, true]),
+        other => panic!("Expected Bool variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tensor_data_from_tensor_proto_double() {
+    let proto = TensorProto {
+        data_type: 11, // DOUBLE
+        dims: vec![2],
+        double_data: vec![0.5, -1.25],
+        ..Default::default()
+    };
+    match TensorData::from_tensor_proto(&proto).unwrap() {
+        TensorData::Float64(v) => assert_eq!(v, vec![0.5, -1.25]),
+        other => panic!("Expected Float64 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tensor_data_filled_int4_packs_nibbles() {
+    let data = TensorData::filled(DataType::Int4, &[3], -1.0);
+    assert_eq!(data.as_bytes().as_ref(), &[0xFF, 0x0F]);
+}
+
+#[test]
+fn test_tensor_data_scalar_bfloat16() {
+    match TensorData::scalar(DataType::BFloat16, 1.0) {
+        TensorData::BFloat16(v) => assert_eq!(v, vec![0x3F80]),
+        other => panic!("Expected BFloat16 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tensor_data_lower_int64_in_range() {
+    let policy = TypeLoweringPolicy::new().without(DataType::Int64);
+    let data = TensorData::Int64(vec![1, -2, i32::MAX as i64]);
+    match data.lower(&policy).unwrap() {
+        TensorData::Int32(v) => assert_eq!(v, vec![1, -2, i32::MAX]),
+        other => panic!("Expected Int32 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tensor_data_lower_int64_overflow() {
+    let policy = TypeLoweringPolicy::new().without(DataType::Int64);
+    let data = TensorData::Int64(vec![0, i64::MAX]);
+    assert!(matches!(
+        data.lower(&policy),
+        Err(ConversionError::ValueOutOfRange(_))
+    ));
+}
+
+#[test]
+fn test_tensor_data_lower_float64_and_bool() {
+    let policy = TypeLoweringPolicy::new();
+    match TensorData::Float64(vec![0.5, f64::INFINITY])
+        .lower(&policy)
+        .unwrap()
+    {
+        TensorData::Float32(v) => assert_eq!(v, vec![0.5, f32::INFINITY]),
+        other => panic!("Expected Float32 variant, got {other:?}"),
+    }
+    assert!(TensorData::Float64(vec![1e300]).lower(&policy).is_err());
+
+    match TensorData::Bool(vec![true, false]).lower(&policy).unwrap() {
+        TensorData::Uint8(v) => assert_eq!(v, vec![1, 0]),
+        other => panic!("Expected Uint8 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tensor_data_narrow_rejects_raw_and_unreachable_types() {
+    assert!(
+        TensorData::Raw(vec![0; 4])
+            .narrow(&DataType::Int32)
+            .is_err()
+    );
+    assert!(TensorData::Int32(vec![1]).narrow(&DataType::Int8).is_err());
+}
+
+#[test]
+fn test_tensor_data_from_tensor_proto_float8() {
+    let proto = TensorProto {
+        data_type: 17, // FLOAT8E4M3FN
+        dims: vec![3],
+        int32_data: vec![0x38, 0xC0, 0x7F],
+        ..Default::default()
+    };
+    let data = TensorData::from_tensor_proto(&proto).unwrap();
+    assert_eq!(data.data_type(), Some(DataType::Float8E4M3FN));
+    match data.upcast_float(&DataType::Float32).unwrap() {
+        TensorData::Float32(v) => {
+            assert_eq!(&v[..2], &[1.0, -2.0]);
+            assert!(v[2].is_nan());
+        }
+        other => panic!("Expected Float32 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tensor_data_from_tensor_proto_float4_odd_length() {
+    let proto = TensorProto {
+        data_type: 23, // FLOAT4E2M1
+        dims: vec![3],
+        int32_data: vec![0x72, 0x0B],
+        ..Default::default()
+    };
+    let data = TensorData::from_tensor_proto(&proto).unwrap();
+    assert_eq!(data.element_count(&DataType::Float4E2M1).unwrap(), 3);
+    match data.upcast_float(&DataType::Float16).unwrap() {
+        TensorData::Float16(v) => {
+            let v: Vec<f32> = v
+                .iter()
+                .map(|&b| half::f16::from_bits(b).to_f32())
+                .collect();
+            assert_eq!(v, vec![1.0, 6.0, -1.5]);
+        }
+        other => panic!("Expected Float16 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tensor_data_float8_lowering_to_float16() {
+    let data = TensorData::filled(DataType::Float8E5M2, &[2], 3.0);
+    let policy = TypeLoweringPolicy::new();
+    match data.lower(&policy).unwrap() {
+        TensorData::Float16(v) => assert_eq!(v, vec![half::f16::from_f32(3.0).to_bits(); 2]),
+        other => panic!("Expected Float16 variant, got {other:?}"),
+    }
+    assert!(
+        TensorData::scalar(DataType::Int32, 1.0)
+            .upcast_float(&DataType::Float32)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_tensor_data_from_tensor_proto_int4_sign_extends() {
+    let proto = TensorProto {
+        data_type: 22, // INT4
+        dims: vec![3],
+        int32_data: vec![0x8F, 0x07],
+        ..Default::default()
+    };
+    let data = TensorData::from_tensor_proto(&proto).unwrap();
+    assert_eq!(data.data_type(), Some(DataType::Int4));
+    assert_eq!(data.element_count(&DataType::Int4).unwrap(), 3);
+    match data.unpack_sub_byte().unwrap() {
+        TensorData::Int8(v) => assert_eq!(v, vec![-1, -8, 7]),
+        other => panic!("Expected Int8 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tensor_data_from_tensor_proto_uint2() {
+    let proto = TensorProto {
+        data_type: 25, // UINT2
+        dims: vec![5],
+        int32_data: vec![0b11_10_01_00, 0b10],
+        ..Default::default()
+    };
+    match TensorData::from_tensor_proto(&proto)
+        .unwrap()
+        .unpack_sub_byte()
+        .unwrap()
+    {
+        TensorData::Uint8(v) => assert_eq!(v, vec![0, 1, 2, 3, 2]),
+        other => panic!("Expected Uint8 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tensor_data_pack_sub_byte_round_trip() {
+    let values = TensorData::Int8(vec![-2, 1, 0, -1, 1]);
+    let packed = values.pack_sub_byte(&DataType::Int2).unwrap();
+    assert_eq!(packed.as_bytes().as_ref(), &[0b11_00_01_10, 0b01]);
+    match packed.unpack_sub_byte().unwrap() {
+        TensorData::Int8(v) => assert_eq!(v, vec![-2, 1, 0, -1, 1]),
+        other => panic!("Expected Int8 variant, got {other:?}"),
+    }
+
+    assert!(matches!(
+        TensorData::Int8(vec![8]).pack_sub_byte(&DataType::Int4),
+        Err(ConversionError::ValueOutOfRange(_))
+    ));
+    assert!(
+        TensorData::Uint8(vec![16])
+            .pack_sub_byte(&DataType::Uint4)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_tensor_data_int2_lowers_to_int4() {
+    let data = TensorData::filled(DataType::Int2, &[3], -2.0);
+    let lowered = data.lower(&TypeLoweringPolicy::new()).unwrap();
+    assert_eq!(lowered.data_type(), Some(DataType::Int4));
+    assert_eq!(lowered.as_bytes().as_ref(), &[0xEE, 0x0E]);
+}
+
+#[test]
+fn test_tensor_data_decode_raw_little_endian() {
+    let raw = [0x00, 0x00, 0x80, 0x3F, 0x00, 0x00, 0x00, 0xC0];
+    match TensorData::decode(&raw, &DataType::Float32).unwrap() {
+        TensorData::Float32(v) => assert_eq!(v, vec![1.0, -2.0]),
+        other => panic!("Expected Float32 variant, got {other:?}"),
+    }
+    match TensorData::decode(&raw, &DataType::Int64).unwrap() {
+        TensorData::Int64(v) => assert_eq!(v, vec![i64::from_le_bytes(raw)]),
+        other => panic!("Expected Int64 variant, got {other:?}"),
+    }
+    match TensorData::decode(&[1, 0, 2], &DataType::Bool).unwrap() {
+        TensorData::Bool(v) => assert_eq!(v, vec![true, false, true]),
+        other => panic!("Expected Bool variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tensor_data_decode_rejects_partial_elements() {
+    assert!(matches!(
+        TensorData::decode(&[0; 6], &DataType::Float32),
+        Err(ConversionError::InvalidTensorData(_))
+    ));
+    assert!(TensorData::decode(&[0; 3], &DataType::Uint16).is_err());
+}
+
+#[test]
+fn test_tensor_data_to_typed_from_raw_proto() {
+    let proto = TensorProto {
+        data_type: 7, // INT64
+        dims: vec![2],
+        raw_data: [5i64, -6].iter().flat_map(|v| v.to_le_bytes()).collect(),
+        ..Default::default()
+    };
+    let data = TensorData::from_tensor_proto(&proto).unwrap();
+    match data.to_typed(&DataType::Int64).unwrap() {
+        TensorData::Int64(v) => assert_eq!(v, vec![5, -6]),
+        other => panic!("Expected Int64 variant, got {other:?}"),
+    }
+    assert!(
+        TensorData::Int32(vec![1])
+            .to_typed(&DataType::Int64)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_tensor_data_decode_packed_int4() {
+    match TensorData::decode(&[0x21, 0xF3], &DataType::Int4)
+        .unwrap()
+        .unpack_sub_byte()
+        .unwrap()
+    {
+        TensorData::Int8(v) => assert_eq!(v, vec![1, 2, 3, -1]),
+        other => panic!("Expected Int8 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_element_count_of() {
+    assert_eq!(element_count_of(&[]).unwrap(), 1);
+    assert_eq!(element_count_of(&[2, 3]).unwrap(), 6);
+    assert_eq!(element_count_of(&[4, 0, 2]).unwrap(), 0);
+    assert!(element_count_of(&[2, -1]).is_err());
+}
+
+#[test]
+fn test_from_tensor_proto_count_mismatch() {
+    let proto = TensorProto {
+        name: "w".to_string(),
+        data_type: 1, // FLOAT
+        dims: vec![2, 2],
+        float_data: vec![1.0, 2.0, 3.0],
+        ..Default::default()
+    };
+    match TensorData::from_tensor_proto(&proto) {
+        Err(ConversionError::InvalidTensorData(msg)) => {
+            assert!(msg.contains("needs 4 elements"), "{msg}");
+            assert!(msg.contains("got 3"), "{msg}");
+        }
+        other => panic!("Expected InvalidTensorData, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_from_tensor_proto_raw_byte_mismatch() {
+    let proto = TensorProto {
+        data_type: 6, // INT32
+        dims: vec![3],
+        raw_data: vec![0; 8],
+        ..Default::default()
+    };
+    assert!(TensorData::from_tensor_proto(&proto).is_err());
+
+    let int4 = TensorProto {
+        data_type: 22, // INT4
+        dims: vec![5],
+        raw_data: vec![0; 2],
+        ..Default::default()
+    };
+    assert!(TensorData::from_tensor_proto(&int4).is_err());
+}
+
+#[test]
+fn test_from_tensor_proto_scalar_and_zero_sized() {
+    let scalar = TensorProto {
+        data_type: 7, // INT64
+        int64_data: vec![42],
+        ..Default::default()
+    };
+    assert_eq!(
+        TensorData::from_tensor_proto(&scalar)
+            .unwrap()
+            .element_count(&DataType::Int64)
+            .unwrap(),
+        1
+    );
+
+    let zero_sized = TensorProto {
+        data_type: 1, // FLOAT
+        dims: vec![0, 3],
+        ..Default::default()
+    };
+    assert!(
+        TensorData::from_tensor_proto(&zero_sized)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_from_tensor_proto_empty_payload_validation() {
+    let proto = TensorProto {
+        data_type: 1, // FLOAT
+        dims: vec![8],
+        ..Default::default()
+    };
+    assert!(TensorData::from_tensor_proto(&proto).is_err());
+    assert!(
+        TensorData::from_tensor_proto_with(&proto, CountValidation::AllowEmpty)
+            .unwrap()
+            .is_empty()
+    );
+
+    let partial = TensorProto {
+        float_data: vec![1.0],
+        ..proto
+    };
+    assert!(TensorData::from_tensor_proto_with(&partial, CountValidation::AllowEmpty).is_err());
+}
+
+#[test]
+fn test_tensor_data_as_bytes_is_little_endian() {
+    let data = TensorData::Int32(vec![1, -2]);
+    assert_eq!(
+        data.as_bytes().as_ref(),
+        &[0x01, 0x00, 0x00, 0x00, 0xFE, 0xFF, 0xFF, 0xFF]
+    );
+    let data = TensorData::Float16(vec![0x3C00]);
+    assert_eq!(data.as_bytes().as_ref(), &[0x00, 0x3C]);
+    let data = TensorData::Bool(vec![true, false]);
+    assert_eq!(data.as_bytes().as_ref(), &[1, 0]);
+}
+
+#[test]
+fn test_tensor_data_bytes_round_trip_through_decode() {
+    let original = TensorData::Float64(vec![1.5, -0.25, f64::MAX]);
+    let decoded = TensorData::decode(&original.as_bytes(), &DataType::Float64).unwrap();
+    match decoded {
+        TensorData::Float64(v) => assert_eq!(v, vec![1.5, -0.25, f64::MAX]),
+        other => panic!("Expected Float64 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tensor_data_into_tensor_proto_moves_bytes() {
+    let bytes = vec![1u8, 2, 3, 4];
+    let ptr = bytes.as_ptr();
+    let proto = TensorData::Uint8(bytes)
+        .into_tensor_proto("u".to_string(), DataType::Uint8, vec![4])
+        .unwrap();
+    assert_eq!(proto.raw_data.as_ptr(), ptr);
+    assert_eq!(proto.raw_data, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn test_tensor_view_borrows_raw_data() {
+    let proto = TensorData::Float32(vec![1.0, 2.0, 3.0, 4.0])
+        .to_tensor_proto("w".to_string(), DataType::Float32, vec![2, 2])
+        .unwrap();
+    let view = TensorView::from_tensor_proto(&proto).unwrap();
+    assert_eq!(view.name, "w");
+    assert_eq!(view.element_count(), 4);
+    assert_eq!(view.bytes.as_ptr(), proto.raw_data.as_ptr());
+    match view.decode().unwrap() {
+        TensorData::Float32(v) => assert_eq!(v, vec![1.0, 2.0, 3.0, 4.0]),
+        other => panic!("Expected Float32 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tensor_view_validation() {
+    let typed = TensorProto {
+        data_type: 1, // FLOAT
+        dims: vec![2],
+        float_data: vec![1.0, 2.0],
+        ..Default::default()
+    };
+    assert!(TensorView::from_tensor_proto(&typed).is_err());
+
+    assert!(TensorView::new("x", DataType::Int64, &[2], &[0; 12]).is_err());
+
+    let int4 = TensorView::new("q", DataType::Int4, &[3], &[0x21, 0x03]).unwrap();
+    assert_eq!(
+        int4.decode()
+            .unwrap()
+            .element_count(&DataType::Int4)
+            .unwrap(),
+        3
+    );
+}
+
+fn sparse(
+    values: TensorProto,
+    indices: Vec<i64>,
+    index_dims: Vec<i64>,
+    dims: Vec<i64>,
+) -> SparseTensorProto {
+    SparseTensorProto {
+        values: Some(values),
+        indices: Some(TensorProto {
+            data_type: 7, // INT64
+            dims: index_dims,
+            int64_data: indices,
+            ..Default::default()
+        }),
+        dims,
+    }
+}
+
+#[test]
+fn test_sparse_tensor_densify_both_layouts() {
+    let values = TensorData::Float32(vec![1.5, -2.0])
+        .to_tensor_proto("w".to_string(), DataType::Float32, vec![2])
+        .unwrap();
+
+    let linear = sparse(values.clone(), vec![1, 5], vec![2], vec![2, 3]);
+    let coords = sparse(values, vec![0, 1, 1, 2], vec![2, 2], vec![2, 3]);
+    for proto in [linear, coords] {
+        match TensorData::from_sparse_tensor_proto(&proto).unwrap() {
+            TensorData::Float32(v) => assert_eq!(v, vec![0.0, 1.5, 0.0, 0.0, 0.0, -2.0]),
+            other => panic!("Expected Float32 variant, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_sparse_tensor_densify_packed_and_typed_fields() {
+    let values = TensorProto {
+        name: "q".to_string(),
+        data_type: 22, // INT4
+        dims: vec![3],
+        int32_data: vec![0x9F, 0x07],
+        ..Default::default()
+    };
+    match TensorData::from_sparse_tensor_proto(&sparse(values, vec![0, 2, 4], vec![3], vec![5]))
+        .unwrap()
+    {
+        TensorData::Int4(p) => {
+            assert_eq!(p.len, 5);
+            assert_eq!(p.unpack_signed(4), vec![-1, 0, -7, 0, 7]);
+        }
+        other => panic!("Expected Int4 variant, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_sparse_tensor_densify_validation() {
+    let values = TensorData::Int32(vec![1, 2])
+        .to_tensor_proto("v".to_string(), DataType::Int32, vec![2])
+        .unwrap();
+    // not ascending
+    let proto = sparse(values.clone(), vec![3, 1], vec![2], vec![4]);
+    assert!(TensorData::from_sparse_tensor_proto(&proto).is_err());
+    // out of range
+    let proto = sparse(values.clone(), vec![1, 4], vec![2], vec![4]);
+    assert!(TensorData::from_sparse_tensor_proto(&proto).is_err());
+    // coordinate outside its dimension, even though the linear index fits
+    let proto = sparse(values.clone(), vec![0, 0, 0, 3], vec![2, 2], vec![2, 2]);
+    assert!(TensorData::from_sparse_tensor_proto(&proto).is_err());
+    // rank mismatch
+    let proto = sparse(values, vec![0, 1], vec![1, 2], vec![4]);
+    assert!(TensorData::from_sparse_tensor_proto(&proto).is_err());
+}
+
+#[test]
+fn test_sparse_tensor_builder_round_trip() {
+    let dense = TensorData::Float32(vec![0.0, 0.0, 3.0, 0.0, -0.0, 0.0, 0.0, 1.0]);
+
+    for layout in [SparseIndexLayout::Linear, SparseIndexLayout::Coordinates] {
+        let proto = SparseTensorBuilder::new("w", DataType::Float32, vec![2, 4])
+            .index_layout(layout)
+            .build(&dense)
+            .unwrap()
+            .expect("tensor is sparse enough");
+        let values = proto.values.as_ref().unwrap();
+        assert_eq!(values.name, "w");
+        // -0.0 is not a zero bit pattern, so it is kept
+        assert_eq!(values.dims, vec![3]);
+
+        match TensorData::from_sparse_tensor_proto(&proto).unwrap() {
+            TensorData::Float32(v) => {
+                assert_eq!(v, vec![0.0, 0.0, 3.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+                assert!(v[4].is_sign_negative());
+            }
+            other => panic!("Expected Float32 variant, got {other:?}"),
+        }
+    }
+
+    let coords = SparseTensorBuilder::new("w", DataType::Float32, vec![2, 4])
+        .index_layout(SparseIndexLayout::Coordinates)
+        .build(&dense)
+        .unwrap()
+        .unwrap();
+    assert_eq!(coords.indices.unwrap().dims, vec![3, 2]);
+}
+
+#[test]
+fn test_sparse_tensor_builder_threshold() {
+    let dense = TensorData::Uint4(PackedBits::pack(&[0, 5, 0, 0, 9], 4));
+    let builder = SparseTensorBuilder::new("q", DataType::Uint4, vec![5]);
+    assert!(builder.build(&dense).unwrap().is_some());
+    assert!(
+        builder
+            .clone()
+            .min_sparsity(0.8)
+            .build(&dense)
+            .unwrap()
+            .is_none()
+    );
+
+    let proto = builder.build(&dense).unwrap().unwrap();
+    match TensorData::from_sparse_tensor_proto(&proto).unwrap() {
+        TensorData::Uint4(p) => assert_eq!(p.unpack(4), vec![0, 5, 0, 0, 9]),
+        other => panic!("Expected Uint4 variant, got {other:?}"),
+    }
+
+    // payload does not match dims
+    assert!(builder.build(&TensorData::Uint8(vec![0; 5])).is_err());
+}
+
+#[test]
+fn test_tensor_data_as_slice() {
+    let data = TensorData::Int64(vec![1, -1]);
+    assert_eq!(data.as_slice::<i64>(), Some(&[1i64, -1][..]));
+    assert_eq!(data.as_slice::<i32>(), None);
+
+    // Float16 bits are not exposed as u16
+    let half = TensorData::Float16(vec![0x3C00]);
+    assert_eq!(half.as_slice::<u16>(), None);
+    assert_eq!(
+        TensorData::Uint16(vec![7]).as_slice::<u16>(),
+        Some(&[7u16][..])
+    );
+}
+
+#[test]
+fn test_tensor_data_get() {
+    let data = TensorData::Float16(vec![0x3C00, 0xC000]);
+    assert_eq!(data.get(1), Some(Scalar::Float(-2.0)));
+    assert_eq!(data.get(2), None);
+
+    let int4 = TensorData::Int4(PackedBits::pack_signed(&[3, -8, 7], 4));
+    assert_eq!(int4.get(1), Some(Scalar::Int(-8)));
+    assert_eq!(int4.get(3), None);
+
+    assert_eq!(
+        TensorData::Uint64(vec![u64::MAX]).get(0),
+        Some(Scalar::Uint(u64::MAX))
+    );
+    assert_eq!(
+        TensorData::Bool(vec![true]).get(0).unwrap().as_i64(),
+        Some(1)
+    );
+    assert_eq!(TensorData::Raw(vec![0; 4]).get(0), None);
+}
+
+#[test]
+fn test_tensor_data_iter_f64() {
+    let raw = TensorData::Float32(vec![1.5, -2.0]).into_bytes();
+    let raw = TensorData::Raw(raw);
+    let values: Vec<f64> = raw.iter_f64(Some(&DataType::Float32)).unwrap().collect();
+    assert_eq!(values, vec![1.5, -2.0]);
+    assert!(raw.iter_f64(None).is_err());
+
+    let e4m3 = TensorData::Float8E4M3FN(vec![0x38, 0x7E]);
+    let values: Vec<f64> = e4m3.iter_f64(None).unwrap().collect();
+    assert_eq!(values, vec![1.0, 448.0]);
+    assert!(e4m3.iter_f64(Some(&DataType::Float32)).is_err());
+}
+
+#[test]
+fn test_tensor_data_to_vec() {
+    let shape = TensorData::Int32(vec![2, -1, 4]);
+    assert_eq!(shape.to_vec_i64().unwrap(), vec![2, -1, 4]);
+    assert_eq!(shape.to_vec_f32().unwrap(), vec![2.0, -1.0, 4.0]);
+
+    let uint4 = TensorData::Uint4(PackedBits::pack(&[15, 1], 4));
+    assert_eq!(uint4.to_vec_i64().unwrap(), vec![15, 1]);
+
+    assert!(TensorData::Float32(vec![1.0]).to_vec_i64().is_err());
+    assert!(matches!(
+        TensorData::Uint64(vec![u64::MAX]).to_vec_i64(),
+        Err(ConversionError::ValueOutOfRange(_))
+    ));
+    assert!(TensorData::Raw(vec![0; 8]).to_vec_i64().is_err());
+    assert_eq!(
+        TensorData::BFloat16(vec![0x3FC0]).to_vec_f32().unwrap(),
+        vec![1.5]
+    );
+}
+
+#[test]
+fn test_to_tensor_proto_rejects_mismatches() {
+    let data = TensorData::Float32(vec![1.0, 2.0]);
+    // wrong dtype would silently reinterpret the bytes
+    assert!(
+        data.to_tensor_proto("x".to_string(), DataType::Int32, vec![2])
+            .is_err()
+    );
+    // wrong element count
+    assert!(
+        data.to_tensor_proto("x".to_string(), DataType::Float32, vec![3])
+            .is_err()
+    );
+    assert!(
+        TensorData::Raw(vec![0; 6])
+            .into_tensor_proto("x".to_string(), DataType::Float32, vec![2])
+            .is_err()
+    );
+    assert!(
+        TensorData::Raw(vec![0; 2])
+            .to_tensor_proto("q".to_string(), DataType::Int4, vec![3])
+            .is_ok()
+    );
+}
@@ -0,0 +1,118 @@
+use webnn_onnx_utils::data_types::DataType;
+use webnn_onnx_utils::protos::onnx::TensorProto;
+use webnn_onnx_utils::tensor::Tensor;
+use webnn_onnx_utils::tensor_data::{Scalar, TensorData};
+
+#[test]
+fn test_tensor_attach_and_to_proto() {
+    let tensor = TensorData::Int64(vec![1, 2, 3, 4, 5, 6])
+        .attach(DataType::Int64, vec![2, 3])
+        .unwrap();
+    assert_eq!(tensor.dtype(), &DataType::Int64);
+    assert_eq!(tensor.shape(), &[2, 3]);
+    assert_eq!(tensor.element_count(), 6);
+
+    let proto = tensor.to_tensor_proto("shape".to_string());
+    assert_eq!(proto.data_type, 7); // INT64
+    assert_eq!(proto.dims, vec![2, 3]);
+    assert_eq!(proto.raw_data.len(), 48);
+}
+
+#[test]
+fn test_tensor_new_validates_and_decodes() {
+    assert!(
+        Tensor::new(
+            DataType::Int32,
+            vec![2],
+            TensorData::Float32(vec![1.0, 2.0])
+        )
+        .is_err()
+    );
+    assert!(
+        Tensor::new(
+            DataType::Float32,
+            vec![3],
+            TensorData::Float32(vec![1.0, 2.0])
+        )
+        .is_err()
+    );
+    assert!(Tensor::new(DataType::Float32, vec![-1], TensorData::Float32(vec![])).is_err());
+
+    let tensor = Tensor::new(DataType::Uint4, vec![3], TensorData::Raw(vec![0x21, 0x03])).unwrap();
+    match tensor.data() {
+        TensorData::Uint4(p) => assert_eq!(p.unpack(4), vec![1, 2, 3]),
+        other => panic!("Expected Uint4 variant, got {other:?}"),
+    }
+    assert_eq!(
+        tensor.into_tensor_proto("q".to_string()).raw_data,
+        vec![0x21, 0x03]
+    );
+}
+
+#[test]
+fn test_tensor_from_tensor_proto_round_trip() {
+    let proto = TensorProto {
+        name: "w".to_string(),
+        data_type: 1, // FLOAT
+        dims: vec![2, 2],
+        float_data: vec![1.0, 2.0, 3.0, 4.0],
+        ..Default::default()
+    };
+    let tensor = Tensor::from_tensor_proto(&proto).unwrap();
+    assert_eq!(tensor.dtype(), &DataType::Float32);
+    assert_eq!(tensor.rank(), 2);
+
+    let back = tensor.to_tensor_proto("w".to_string());
+    let again = Tensor::from_tensor_proto(&back).unwrap();
+    assert_eq!(
+        again.data().as_slice::<f32>(),
+        Some(&[1.0f32, 2.0, 3.0, 4.0][..])
+    );
+
+    let short = TensorProto {
+        dims: vec![3, 2],
+        ..proto
+    };
+    assert!(Tensor::from_tensor_proto(&short).is_err());
+}
+
+#[test]
+fn test_tensor_constructors() {
+    let scalar = Tensor::scalar(DataType::Int32, 7.0);
+    assert_eq!(scalar.rank(), 0);
+    assert_eq!(scalar.get(&[]), Some(Scalar::Int(7)));
+
+    let ones = Tensor::filled(DataType::Float16, vec![2, 3], 1.0).unwrap();
+    assert_eq!(ones.element_count(), 6);
+    assert_eq!(ones.get(&[1, 2]), Some(Scalar::Float(1.0)));
+    assert!(Tensor::filled(DataType::Float32, vec![2, -3], 1.0).is_err());
+}
+
+#[test]
+fn test_tensor_reshape() {
+    let tensor = TensorData::Int32((0..24).collect())
+        .attach(DataType::Int32, vec![2, 3, 4])
+        .unwrap();
+
+    let reshaped = tensor.clone().reshape(&[0, -1]).unwrap();
+    assert_eq!(reshaped.shape(), &[2, 12]);
+    assert_eq!(reshaped.get(&[1, 0]), Some(Scalar::Int(12)));
+
+    assert_eq!(tensor.clone().reshape(&[-1]).unwrap().shape(), &[24]);
+    assert!(tensor.clone().reshape(&[5, -1]).is_err());
+    assert!(tensor.clone().reshape(&[-1, -1]).is_err());
+    assert!(tensor.clone().reshape(&[4, 4]).is_err());
+    assert!(tensor.reshape(&[0, 0, 0, 0]).is_err());
+}
+
+#[test]
+fn test_tensor_indexing() {
+    let tensor = TensorData::Float32((0..24).map(|x| x as f32).collect())
+        .attach(DataType::Float32, vec![2, 3, 4])
+        .unwrap();
+    assert_eq!(tensor.strides(), vec![12, 4, 1]);
+    assert_eq!(tensor.flat_index(&[1, 2, 3]), Some(23));
+    assert_eq!(tensor.get(&[1, 0, 2]), Some(Scalar::Float(14.0)));
+    assert_eq!(tensor.get(&[0, 3, 0]), None);
+    assert_eq!(tensor.get(&[0, 0]), None);
+}